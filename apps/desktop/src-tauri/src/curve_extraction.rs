@@ -0,0 +1,2184 @@
+use image::{DynamicImage, Rgb, RgbImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Configuration constants - enhanced for better detection
+const BIN_SIZE: f64 = 0.01;
+const MIN_GRID_SIZE: usize = 5;
+const MAX_GRID_SIZE: usize = 50;
+const MIN_COLOR_PIXELS: usize = 500; // Minimum pixels for color detection
+const COLOR_TOLERANCE: f32 = 0.15; // Color tolerance for better matching
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphConfig {
+    pub x_min: f64,
+    pub x_max: f64,
+    pub y_min: f64,
+    pub y_max: f64,
+    pub x_scale: f64,
+    pub y_scale: f64,
+    pub x_scale_type: String,
+    pub y_scale_type: String,
+    pub graph_type: String,
+    pub x_axis_name: Option<String>,
+    pub y_axis_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedColor {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub color: String,
+    pub pixel_count: usize,
+    pub hsv: Option<HSV>,
+    pub confidence: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HSV {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurvePoint {
+    pub x: f64,
+    pub y: f64,
+    pub label: Option<String>,
+    pub confidence: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveData {
+    pub name: String,
+    pub color: String,
+    pub points: Vec<CurvePoint>,
+    pub representation: Option<String>,
+    pub point_count: Option<usize>,
+    pub metadata: Option<CurveMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveMetadata {
+    pub min_x: Option<f64>,
+    pub max_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_y: Option<f64>,
+    pub average_slope: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionResult {
+    pub success: bool,
+    pub curves: Vec<CurveData>,
+    pub total_points: usize,
+    pub processing_time: f64,
+    pub error: Option<String>,
+    pub metadata: Option<ExtractionMetadata>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionMetadata {
+    pub image_width: Option<usize>,
+    pub image_height: Option<usize>,
+    pub detected_colors: Option<usize>,
+    pub extraction_method: Option<String>,
+    pub quality_score: Option<f32>,
+}
+
+// Enhanced color ranges based on Python legacy implementation and graph analysis
+pub struct ColorRange {
+    pub lower: [u8; 3],
+    pub upper: [u8; 3],
+    pub display_color: &'static str,
+    pub base_color: &'static str,
+    pub tolerance: f32, // Additional tolerance for each color
+}
+
+pub fn get_color_ranges() -> HashMap<&'static str, ColorRange> {
+    let mut ranges = HashMap::new();
+    
+    // Enhanced red detection for semiconductor graphs
+    ranges.insert("red", ColorRange {
+        lower: [0, 120, 100],
+        upper: [15, 255, 255],
+        display_color: "#FF0000",
+        base_color: "red",
+        tolerance: 0.12,
+    });
+    
+    ranges.insert("red2", ColorRange {
+        lower: [165, 120, 100],
+        upper: [180, 255, 255],
+        display_color: "#FF0000",
+        base_color: "red",
+        tolerance: 0.12,
+    });
+    
+    // Enhanced blue detection for semiconductor graphs
+    ranges.insert("blue", ColorRange {
+        lower: [85, 120, 100],
+        upper: [135, 255, 255],
+        display_color: "#0000FF",
+        base_color: "blue",
+        tolerance: 0.10,
+    });
+    
+    // Enhanced green detection
+    ranges.insert("green", ColorRange {
+        lower: [35, 120, 100],
+        upper: [85, 255, 255],
+        display_color: "#00FF00",
+        base_color: "green",
+        tolerance: 0.15,
+    });
+    
+    // Enhanced yellow detection
+    ranges.insert("yellow", ColorRange {
+        lower: [10, 120, 100],
+        upper: [45, 255, 255],
+        display_color: "#FFFF00",
+        base_color: "yellow",
+        tolerance: 0.18,
+    });
+    
+    // Enhanced cyan detection
+    ranges.insert("cyan", ColorRange {
+        lower: [75, 120, 100],
+        upper: [105, 255, 255],
+        display_color: "#00FFFF",
+        base_color: "cyan",
+        tolerance: 0.12,
+    });
+    
+    // Enhanced magenta detection
+    ranges.insert("magenta", ColorRange {
+        lower: [135, 120, 100],
+        upper: [175, 255, 255],
+        display_color: "#FF00FF",
+        base_color: "magenta",
+        tolerance: 0.15,
+    });
+    
+    // Enhanced orange detection
+    ranges.insert("orange", ColorRange {
+        lower: [3, 120, 100],
+        upper: [25, 255, 255],
+        display_color: "#FFA500",
+        base_color: "orange",
+        tolerance: 0.20,
+    });
+    
+    // Enhanced purple detection
+    ranges.insert("purple", ColorRange {
+        lower: [120, 120, 100],
+        upper: [150, 255, 255],
+        display_color: "#800080",
+        base_color: "purple",
+        tolerance: 0.15,
+    });
+    
+    ranges
+}
+
+// Enhanced RGB to HSV conversion with better precision
+fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let r = r as f32 / 255.0;
+    let g = g as f32 / 255.0;
+    let b = b as f32 / 255.0;
+    
+    let max = r.max(g.max(b));
+    let min = r.min(g.min(b));
+    let delta = max - min;
+    
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * (((g - b) / delta) % 6.0)
+    } else if max == g {
+        60.0 * (((b - r) / delta) + 2.0)
+    } else {
+        60.0 * (((r - g) / delta) + 4.0)
+    };
+    
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    let v = max;
+    
+    (h, s, v)
+}
+
+// Enhanced color matching with tolerance
+fn color_matches_range(h: f32, s: f32, v: f32, range: &ColorRange) -> bool {
+    let h_normalized = h / 2.0; // Convert to 0-180 range
+    
+    // Handle red color wraparound
+    let h_match = if range.lower[0] > range.upper[0] {
+        h_normalized >= range.lower[0] as f32 || h_normalized <= range.upper[0] as f32
+    } else {
+        h_normalized >= range.lower[0] as f32 && h_normalized <= range.upper[0] as f32
+    };
+    
+    // More lenient saturation and value matching with tolerance
+    let s_match = s * 255.0 >= range.lower[1] as f32 * (1.0 - range.tolerance) 
+                  && s * 255.0 <= range.upper[1] as f32 * (1.0 + range.tolerance);
+    let v_match = v * 255.0 >= range.lower[2] as f32 * (1.0 - range.tolerance) 
+                  && v * 255.0 <= range.upper[2] as f32 * (1.0 + range.tolerance);
+    
+    h_match && s_match && v_match
+}
+
+// Perceptual color matching in CIELAB. `color_matches_range` does
+// axis-aligned HSV interval tests with ad-hoc per-color tolerance fudge
+// factors, which both over-matches desaturated background pixels and
+// under-matches anti-aliased curve edges. This converts pixels to CIELAB
+// and matches by thresholding CIE76 Euclidean ΔE distance, which gives
+// uniform tolerance behavior across hues.
+const DEFAULT_DELTA_E_TOLERANCE: f32 = 15.0;
+
+fn srgb_channel_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// sRGB -> linear RGB -> CIE XYZ via the standard D65 matrix.
+fn rgb_to_xyz(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let rl = srgb_channel_to_linear(r as f32 / 255.0);
+    let gl = srgb_channel_to_linear(g as f32 / 255.0);
+    let bl = srgb_channel_to_linear(b as f32 / 255.0);
+
+    let x = rl * 0.4124564 + gl * 0.3575761 + bl * 0.1804375;
+    let y = rl * 0.2126729 + gl * 0.7151522 + bl * 0.0721750;
+    let z = rl * 0.0193339 + gl * 0.1191920 + bl * 0.9503041;
+    (x, y, z)
+}
+
+// CIE D65 reference white.
+const D65_XN: f32 = 0.95047;
+const D65_YN: f32 = 1.0;
+const D65_ZN: f32 = 1.08883;
+
+fn xyz_to_lab(x: f32, y: f32, z: f32) -> (f32, f32, f32) {
+    const DELTA: f32 = 6.0 / 29.0;
+    fn f(t: f32) -> f32 {
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    }
+
+    let fx = f(x / D65_XN);
+    let fy = f(y / D65_YN);
+    let fz = f(z / D65_ZN);
+
+    let l = 116.0 * fy - 16.0;
+    let a = 500.0 * (fx - fy);
+    let b = 200.0 * (fy - fz);
+    (l, a, b)
+}
+
+pub fn rgb_to_lab(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (x, y, z) = rgb_to_xyz(r, g, b);
+    xyz_to_lab(x, y, z)
+}
+
+// CIE76 ΔE: plain Euclidean distance in Lab space.
+fn delta_e76(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    let dl = a.0 - b.0;
+    let da = a.1 - b.1;
+    let db = a.2 - b.2;
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+fn hex_to_rgb(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some([r, g, b])
+}
+
+fn color_matches_lab(pixel: [u8; 3], target_lab: (f32, f32, f32), tolerance: f32) -> bool {
+    let lab = rgb_to_lab(pixel[0], pixel[1], pixel[2]);
+    delta_e76(lab, target_lab) <= tolerance
+}
+
+fn create_lab_mask(image: &RgbImage, target_lab: (f32, f32, f32), tolerance: f32) -> Vec<bool> {
+    image
+        .pixels()
+        .map(|p| color_matches_lab([p[0], p[1], p[2]], target_lab, tolerance))
+        .collect()
+}
+
+/// Same pipeline as `extract_curves`, but matches each selected color's Lab
+/// centroid by ΔE distance instead of HSV box tests.
+pub fn extract_curves_perceptual(
+    image_data: &[u8],
+    selected_colors: &[String],
+    config: &GraphConfig,
+    match_tolerance: Option<f32>,
+) -> Result<ExtractionResult, String> {
+    let tolerance = match_tolerance.unwrap_or(DEFAULT_DELTA_E_TOLERANCE);
+
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let rgb_image = image.to_rgb8();
+    let (width, height) = rgb_image.dimensions();
+
+    let color_ranges = get_color_ranges();
+    let mut processed_base_colors = std::collections::HashSet::new();
+
+    let mut curves = Vec::new();
+    let mut total_points = 0usize;
+
+    for color_name in selected_colors {
+        let color_key = color_name.to_lowercase();
+        let Some(color_range) = color_ranges.get(color_key.as_str()) else {
+            continue;
+        };
+        if !processed_base_colors.insert(color_range.base_color) {
+            continue;
+        }
+        let Some(rgb) = hex_to_rgb(color_range.display_color) else {
+            continue;
+        };
+        let target_lab = rgb_to_lab(rgb[0], rgb[1], rgb[2]);
+
+        let mask = create_lab_mask(&rgb_image, target_lab, tolerance);
+        let cleaned_mask = morphological_open(&mask, width as usize, height as usize);
+        let min_size = (width * height / 1000).max(1000) as usize;
+        let filtered_mask = filter_connected_components(&cleaned_mask, width as usize, height as usize, min_size);
+
+        let mut points = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let idx = (y * width + x) as usize;
+                if filtered_mask[idx] {
+                    let logical_x = if config.x_scale_type == "linear" {
+                        x as f64 * (config.x_max - config.x_min) / width as f64 + config.x_min
+                    } else {
+                        let f = x as f64 / width as f64;
+                        let log_x = config.x_min.ln() + f * (config.x_max.ln() - config.x_min.ln());
+                        log_x.exp()
+                    };
+
+                    let logical_y = if config.y_scale_type == "linear" {
+                        (height - y) as f64 * (config.y_max - config.y_min) / height as f64 + config.y_min
+                    } else {
+                        let f = (height - y) as f64 / height as f64;
+                        let log_y = config.y_min.ln() + f * (config.y_max.ln() - config.y_min.ln());
+                        log_y.exp()
+                    };
+
+                    points.push((logical_x, logical_y));
+                }
+            }
+        }
+
+        if points.is_empty() {
+            continue;
+        }
+
+        let (curve_points, point_count) = bin_and_smooth_curve(color_range.base_color, points, config);
+        total_points += point_count;
+
+        curves.push(CurveData {
+            name: color_range.base_color.to_string(),
+            color: color_range.display_color.to_string(),
+            point_count: Some(point_count),
+            points: curve_points,
+            representation: Some(color_range.base_color.to_string()),
+            metadata: None,
+        });
+    }
+
+    Ok(ExtractionResult {
+        success: true,
+        curves,
+        total_points,
+        processing_time: 0.0,
+        error: None,
+        metadata: Some(ExtractionMetadata {
+            image_width: Some(width as usize),
+            image_height: Some(height as usize),
+            detected_colors: Some(color_ranges.len()),
+            extraction_method: Some("perceptual_lab".to_string()),
+            quality_score: None,
+        }),
+    })
+}
+
+// Auto-detect grid size using FFT - enhanced version
+fn auto_detect_grid_size(image: &RgbImage) -> (usize, usize) {
+    let (width, height) = image.dimensions();
+    let size = width.min(height) as usize;
+    
+    // Convert to grayscale for analysis
+    let gray: Vec<f32> = image.pixels()
+        .map(|p| (p[0] as f32 * 0.299 + p[1] as f32 * 0.587 + p[2] as f32 * 0.114) / 255.0)
+        .collect();
+    
+    // Enhanced grid detection using edge detection
+    let mut edges = 0;
+    for y in 1..height-1 {
+        for x in 1..width-1 {
+            let idx = (y * width + x) as usize;
+            let gx = gray[idx + 1] - gray[idx - 1];
+            let gy = gray[idx + width as usize] - gray[idx - width as usize];
+            let gradient = (gx * gx + gy * gy).sqrt();
+            if gradient > 0.1 {
+                edges += 1;
+            }
+        }
+    }
+    
+    // Estimate grid size based on edge density
+    let edge_density = edges as f32 / (width * height) as f32;
+    let estimated_size = if edge_density > 0.05 {
+        (size / 80).max(MIN_GRID_SIZE).min(MAX_GRID_SIZE)
+    } else {
+        (size / 100).max(MIN_GRID_SIZE).min(MAX_GRID_SIZE)
+    };
+    
+    (estimated_size, estimated_size)
+}
+
+// Enhanced color mask creation
+fn create_color_mask(image: &RgbImage, range: &ColorRange) -> Vec<bool> {
+    image.pixels()
+        .map(|pixel| {
+            let (h, s, v) = rgb_to_hsv(pixel[0], pixel[1], pixel[2]);
+            color_matches_range(h, s, v, range)
+        })
+        .collect()
+}
+
+// Row-parallel color mask creation: each pixel's match test is independent,
+// so rows are chunked across threads via rayon. Produces a bit-identical
+// mask to `create_color_mask`, just faster on multi-megapixel scans.
+fn create_color_mask_parallel(image: &RgbImage, range: &ColorRange) -> Vec<bool> {
+    let width = image.width() as usize;
+    image
+        .as_raw()
+        .par_chunks(width * 3)
+        .flat_map(|row| {
+            row.chunks(3)
+                .map(|px| {
+                    let (h, s, v) = rgb_to_hsv(px[0], px[1], px[2]);
+                    color_matches_range(h, s, v, range)
+                })
+                .collect::<Vec<bool>>()
+        })
+        .collect()
+}
+
+// Enhanced morphological operations
+fn morphological_open(mask: &[bool], width: usize, height: usize) -> Vec<bool> {
+    let mut result = vec![false; mask.len()];
+    
+    // Enhanced 3x3 erosion followed by dilation
+    for y in 1..height-1 {
+        for x in 1..width-1 {
+            let idx = y * width + x;
+            
+            // Check 3x3 neighborhood with enhanced logic
+            let mut all_true = true;
+            let mut neighbor_count = 0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let ny = (y as i32 + dy) as usize;
+                    let nx = (x as i32 + dx) as usize;
+                    let nidx = ny * width + nx;
+                    if mask[nidx] {
+                        neighbor_count += 1;
+                    } else {
+                        all_true = false;
+                    }
+                }
+            }
+            
+            // Enhanced erosion: require at least 6 neighbors (including center)
+            result[idx] = all_true && neighbor_count >= 6;
+        }
+    }
+    
+    // Enhanced dilation
+    let mut final_result = vec![false; mask.len()];
+    for y in 1..height-1 {
+        for x in 1..width-1 {
+            let idx = y * width + x;
+            
+            // Check 3x3 neighborhood
+            let mut any_true = false;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let ny = (y as i32 + dy) as usize;
+                    let nx = (x as i32 + dx) as usize;
+                    let nidx = ny * width + nx;
+                    if result[nidx] {
+                        any_true = true;
+                        break;
+                    }
+                }
+                if any_true { break; }
+            }
+            
+            final_result[idx] = any_true;
+        }
+    }
+    
+    final_result
+}
+
+// Enhanced connected components filtering
+fn filter_connected_components(mask: &[bool], width: usize, height: usize, min_size: usize) -> Vec<bool> {
+    let mut visited = vec![false; mask.len()];
+    let mut result = vec![false; mask.len()];
+    
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if mask[idx] && !visited[idx] {
+                let mut component = Vec::new();
+                let mut stack = vec![(x, y)];
+                
+                while let Some((cx, cy)) = stack.pop() {
+                    let cidx = cy * width + cx;
+                    if visited[cidx] || !mask[cidx] {
+                        continue;
+                    }
+                    
+                    visited[cidx] = true;
+                    component.push(cidx);
+                    
+                    // Add neighbors with enhanced connectivity
+                    for dy in -1..=1 {
+                        for dx in -1..=1 {
+                            if dx == 0 && dy == 0 { continue; }
+                            let nx = cx as i32 + dx;
+                            let ny = cy as i32 + dy;
+                            if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
+                                stack.push((nx as usize, ny as usize));
+                            }
+                        }
+                    }
+                }
+                
+                // Enhanced size filtering with aspect ratio check
+                if component.len() >= min_size {
+                    // Calculate bounding box
+                    let mut min_x = width;
+                    let mut max_x = 0;
+                    let mut min_y = height;
+                    let mut max_y = 0;
+                    
+                    for &idx in &component {
+                        let x = idx % width;
+                        let y = idx / width;
+                        min_x = min_x.min(x);
+                        max_x = max_x.max(x);
+                        min_y = min_y.min(y);
+                        max_y = max_y.max(y);
+                    }
+                    
+                    let width_comp = max_x - min_x + 1;
+                    let height_comp = max_y - min_y + 1;
+                    let aspect_ratio = width_comp as f32 / height_comp as f32;
+                    
+                    // Filter by aspect ratio (curves should be more horizontal than vertical)
+                    if aspect_ratio > 0.3 && aspect_ratio < 10.0 {
+                        for &idx in &component {
+                            result[idx] = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    result
+}
+
+// Union-find connected-component labeling. The DFS/stack-based labeling
+// above grows its stack with component size, which gets expensive on large
+// scans with a few big curve blobs; this does a first pass that assigns
+// provisional labels while scanning rows and unions each pixel with its
+// already-visited 8-neighbors, then a second pass that flattens labels and
+// accumulates per-label pixel count plus bounding box, so component stats
+// come out of a single flatten/accumulate pass instead of a fresh
+// traversal per component. Filtering is bit-identical to
+// `filter_connected_components`.
+struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind { parent: (0..n).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut cur = x;
+        while self.parent[cur] != root {
+            let next = self.parent[cur];
+            self.parent[cur] = root;
+            cur = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let ra = self.find(a);
+        let rb = self.find(b);
+        if ra != rb {
+            self.parent[ra] = rb;
+        }
+    }
+}
+
+fn filter_connected_components_fast(mask: &[bool], width: usize, height: usize, min_size: usize) -> Vec<bool> {
+    let mut uf = UnionFind::new(mask.len());
+
+    // First pass: union each foreground pixel with its already-visited
+    // 8-neighbors (up-left, up, up-right, left).
+    const PRIOR_NEIGHBORS: [(i64, i64); 4] = [(-1, -1), (0, -1), (1, -1), (-1, 0)];
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if !mask[idx] {
+                continue;
+            }
+            for (dx, dy) in PRIOR_NEIGHBORS {
+                let nx = x as i64 + dx;
+                let ny = y as i64 + dy;
+                if nx >= 0 && nx < width as i64 && ny >= 0 && ny < height as i64 {
+                    let nidx = ny as usize * width + nx as usize;
+                    if mask[nidx] {
+                        uf.union(idx, nidx);
+                    }
+                }
+            }
+        }
+    }
+
+    // Second pass: flatten labels and accumulate per-label pixel count
+    // plus bounding box (count, min_x, max_x, min_y, max_y).
+    let mut stats: HashMap<usize, (usize, usize, usize, usize, usize)> = HashMap::new();
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if !mask[idx] {
+                continue;
+            }
+            let root = uf.find(idx);
+            let entry = stats.entry(root).or_insert((0, width, 0, height, 0));
+            entry.0 += 1;
+            entry.1 = entry.1.min(x);
+            entry.2 = entry.2.max(x);
+            entry.3 = entry.3.min(y);
+            entry.4 = entry.4.max(y);
+        }
+    }
+
+    let mut result = vec![false; mask.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            if !mask[idx] {
+                continue;
+            }
+            let root = uf.find(idx);
+            let &(count, min_x, max_x, min_y, max_y) = stats.get(&root).unwrap();
+            if count < min_size {
+                continue;
+            }
+            let aspect_ratio = (max_x - min_x + 1) as f32 / (max_y - min_y + 1) as f32;
+            if aspect_ratio > 0.3 && aspect_ratio < 10.0 {
+                result[idx] = true;
+            }
+        }
+    }
+
+    result
+}
+
+/// Performance path for batch extraction over many pages and many colors:
+/// parallelizes mask generation across pixel rows and replaces the
+/// DFS-based component labeling with two-pass union-find, while keeping
+/// results bit-identical to `extract_curves`.
+pub fn extract_curves_fast(
+    image_data: &[u8],
+    selected_colors: &[String],
+    config: &GraphConfig,
+) -> Result<ExtractionResult, String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let rgb_image = image.to_rgb8();
+    let (width, height) = rgb_image.dimensions();
+
+    let color_ranges = get_color_ranges();
+    let mut base_color_points: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+
+    for color_name in selected_colors {
+        let color_key = color_name.to_lowercase();
+        let Some(color_range) = color_ranges.get(color_key.as_str()) else {
+            continue;
+        };
+
+        let mask = create_color_mask_parallel(&rgb_image, color_range);
+        let cleaned_mask = morphological_open(&mask, width as usize, height as usize);
+        let min_size = (width * height / 1000).max(1000) as usize;
+        let filtered_mask = filter_connected_components_fast(&cleaned_mask, width as usize, height as usize, min_size);
+
+        let mut points = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let idx = (y * width + x) as usize;
+                if filtered_mask[idx] {
+                    let logical_x = if config.x_scale_type == "linear" {
+                        x as f64 * (config.x_max - config.x_min) / width as f64 + config.x_min
+                    } else {
+                        let f = x as f64 / width as f64;
+                        let log_x = config.x_min.ln() + f * (config.x_max.ln() - config.x_min.ln());
+                        log_x.exp()
+                    };
+
+                    let logical_y = if config.y_scale_type == "linear" {
+                        (height - y) as f64 * (config.y_max - config.y_min) / height as f64 + config.y_min
+                    } else {
+                        let f = (height - y) as f64 / height as f64;
+                        let log_y = config.y_min.ln() + f * (config.y_max.ln() - config.y_min.ln());
+                        log_y.exp()
+                    };
+
+                    points.push((logical_x, logical_y));
+                }
+            }
+        }
+
+        let base_color = color_range.base_color.to_string();
+        base_color_points.entry(base_color).or_insert_with(Vec::new).extend(points);
+    }
+
+    let mut curves = Vec::new();
+    let mut total_points = 0usize;
+
+    for (base_color, points) in base_color_points {
+        if points.is_empty() {
+            continue;
+        }
+
+        let mut data: HashMap<i64, Vec<f64>> = HashMap::new();
+        for (x, y) in points {
+            let bin_x = (x / BIN_SIZE).round() as i64;
+            data.entry(bin_x).or_insert_with(Vec::new).push(y);
+        }
+
+        let mut final_points = Vec::new();
+        for (bin_x, y_vals) in data {
+            if y_vals.is_empty() {
+                continue;
+            }
+
+            let mut sorted_y = y_vals.clone();
+            sorted_y.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+            let median = if sorted_y.len() % 2 == 0 {
+                (sorted_y[sorted_y.len() / 2 - 1] + sorted_y[sorted_y.len() / 2]) / 2.0
+            } else {
+                sorted_y[sorted_y.len() / 2]
+            };
+
+            let filtered: Vec<f64> = sorted_y.into_iter().filter(|&y| (y - median).abs() < 2.0 * 0.2).collect();
+
+            if !filtered.is_empty() {
+                let x_val = bin_x as f64 * BIN_SIZE;
+                let y_val = filtered.iter().sum::<f64>() / filtered.len() as f64;
+                final_points.push((x_val, y_val));
+            }
+        }
+
+        final_points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        total_points += final_points.len();
+
+        let x_vals: Vec<f64> = final_points.iter().map(|(x, _)| *x).collect();
+        let y_vals: Vec<f64> = final_points.iter().map(|(_, y)| *y).collect();
+
+        let smooth_window = match base_color.as_str() {
+            "red" => (y_vals.len() / 10).max(5).min(25),
+            "blue" => (y_vals.len() / 12).max(5).min(20),
+            _ => (y_vals.len() / 15).max(3).min(15),
+        };
+
+        let smoothed_y = if y_vals.len() > smooth_window {
+            savgol_smooth_poly(&y_vals, smooth_window, 2)
+        } else {
+            y_vals.clone()
+        };
+
+        let scaled_x: Vec<f64> = x_vals.iter().map(|x| x * config.x_scale).collect();
+        let scaled_y: Vec<f64> = smoothed_y.iter().map(|y| y * config.y_scale).collect();
+
+        let curve_points: Vec<CurvePoint> = scaled_x
+            .iter()
+            .zip(scaled_y.iter())
+            .map(|(x, y)| CurvePoint {
+                x: *x,
+                y: *y,
+                label: Some(format!("{:.3}, {:.3}", x, y)),
+                confidence: None,
+            })
+            .collect();
+
+        let display_color = color_ranges.get(base_color.as_str()).map(|r| r.display_color.to_string()).unwrap_or_else(|| "#000000".to_string());
+
+        curves.push(CurveData {
+            name: base_color.clone(),
+            color: display_color,
+            points: curve_points,
+            representation: Some(base_color.clone()),
+            point_count: Some(final_points.len()),
+            metadata: None,
+        });
+    }
+
+    Ok(ExtractionResult {
+        success: true,
+        curves,
+        total_points,
+        processing_time: 0.0,
+        error: None,
+        metadata: Some(ExtractionMetadata {
+            image_width: Some(width as usize),
+            image_height: Some(height as usize),
+            detected_colors: Some(color_ranges.len()),
+            extraction_method: Some("curve_extraction_fast".to_string()),
+            quality_score: None,
+        }),
+    })
+}
+
+// Enhanced Savitzky-Golay smoothing
+pub fn savgol_smooth(data: &[f64], window: usize) -> Vec<f64> {
+    if data.len() <= window {
+        return data.to_vec();
+    }
+    
+    let mut result = Vec::with_capacity(data.len());
+    let half_window = window / 2;
+    
+    for i in 0..data.len() {
+        let start = i.saturating_sub(half_window);
+        let end = (i + half_window + 1).min(data.len());
+        
+        let slice = &data[start..end];
+        let sum: f64 = slice.iter().sum();
+        let count = slice.len();
+        
+        // Enhanced smoothing with weighted average
+        let weight_sum: f64 = slice.iter().enumerate().map(|(j, _)| {
+            let dist = (j as f64 - count as f64 / 2.0).abs();
+            1.0 / (1.0 + dist * 0.5)
+        }).sum();
+        
+        let weighted_sum: f64 = slice.iter().enumerate().map(|(j, &val)| {
+            let dist = (j as f64 - count as f64 / 2.0).abs();
+            let weight = 1.0 / (1.0 + dist * 0.5);
+            val * weight
+        }).sum();
+        
+        result.push(weighted_sum / weight_sum);
+    }
+
+    result
+}
+
+// Real Savitzky-Golay smoothing: `savgol_smooth` above is actually a
+// distance-weighted moving average, which flattens peaks and distorts the
+// knee region of I-V curves. This fits a degree-`d` polynomial by
+// least-squares over each 2m+1 window and convolves the resulting fixed
+// coefficients across the interior samples, preserving curvature far
+// better (which in turn makes `CurveMetadata::average_slope` meaningful).
+fn transpose_matrix(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let rows = a.len();
+    let cols = a[0].len();
+    let mut t = vec![vec![0.0; rows]; cols];
+    for i in 0..rows {
+        for j in 0..cols {
+            t[j][i] = a[i][j];
+        }
+    }
+    t
+}
+
+fn matmul(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let rows = a.len();
+    let inner = b.len();
+    let cols = b[0].len();
+    let mut result = vec![vec![0.0; cols]; rows];
+    for i in 0..rows {
+        for k in 0..inner {
+            if a[i][k] == 0.0 {
+                continue;
+            }
+            for j in 0..cols {
+                result[i][j] += a[i][k] * b[k][j];
+            }
+        }
+    }
+    result
+}
+
+fn invert_square_matrix(mut m: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = m.len();
+    let mut inv = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        inv[i][i] = 1.0;
+    }
+
+    for col in 0..n {
+        let pivot_row = (col..n).max_by(|&r1, &r2| m[r1][col].abs().partial_cmp(&m[r2][col].abs()).unwrap())?;
+        if m[pivot_row][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot_row);
+        inv.swap(col, pivot_row);
+
+        let pivot = m[col][col];
+        for c in 0..n {
+            m[col][c] /= pivot;
+            inv[col][c] /= pivot;
+        }
+
+        for row in 0..n {
+            if row == col {
+                continue;
+            }
+            let factor = m[row][col];
+            if factor == 0.0 {
+                continue;
+            }
+            for c in 0..n {
+                m[row][c] -= factor * m[col][c];
+                inv[row][c] -= factor * inv[col][c];
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+// Builds the Vandermonde matrix A[i][j] = (i-m)^j for a window of 2m+1
+// points and returns the convolution coefficient vector: the first row of
+// (A^T A)^-1 A^T, i.e. the linear combination of the window's y-values
+// that gives the fitted polynomial's value at the center (x=0).
+fn savgol_coefficients(window: usize, degree: usize) -> Option<Vec<f64>> {
+    let m = (window / 2) as i64;
+    let mut a = vec![vec![0.0; degree + 1]; window];
+    for i in 0..window {
+        let x = (i as i64 - m) as f64;
+        let mut pow = 1.0;
+        for j in 0..=degree {
+            a[i][j] = pow;
+            pow *= x;
+        }
+    }
+
+    let at = transpose_matrix(&a);
+    let ata = matmul(&at, &a);
+    let ata_inv = invert_square_matrix(ata)?;
+    let coeff_matrix = matmul(&ata_inv, &at);
+    Some(coeff_matrix[0].clone())
+}
+
+// Fits a degree-`d` polynomial to whatever window is available near an
+// edge and evaluates it at the edge position, instead of truncating the
+// window (which biases the boundary samples).
+fn fit_and_evaluate_edge(data: &[f64], i: usize, m: usize, degree: usize) -> f64 {
+    let start = i.saturating_sub(m);
+    let end = (i + m + 1).min(data.len());
+    let window_points = end - start;
+
+    if window_points < degree + 1 {
+        return data[i];
+    }
+
+    let mut a = vec![vec![0.0; degree + 1]; window_points];
+    for (row, idx) in (start..end).enumerate() {
+        let x = (idx as f64) - (start as f64);
+        let mut pow = 1.0;
+        for j in 0..=degree {
+            a[row][j] = pow;
+            pow *= x;
+        }
+    }
+
+    let at = transpose_matrix(&a);
+    let ata = matmul(&at, &a);
+    let Some(ata_inv) = invert_square_matrix(ata) else {
+        return data[i];
+    };
+
+    let aty: Vec<f64> = (0..=degree)
+        .map(|j| (0..window_points).map(|row| at[j][row] * data[start + row]).sum())
+        .collect();
+    let beta: Vec<f64> = (0..=degree)
+        .map(|r| (0..=degree).map(|c| ata_inv[r][c] * aty[c]).sum())
+        .collect();
+
+    let x_eval = (i as f64) - (start as f64);
+    let mut pow = 1.0;
+    let mut value = 0.0;
+    for coeff in &beta {
+        value += coeff * pow;
+        pow *= x_eval;
+    }
+    value
+}
+
+/// Genuine Savitzky-Golay filter: a degree-`d` polynomial least-squares fit
+/// over a window of `2m+1` points, convolved across the interior samples.
+/// Edge samples (within `m` of either end) are handled by fitting the
+/// polynomial to the available window and evaluating it at the edge
+/// position rather than truncating.
+pub fn savgol_smooth_poly(data: &[f64], window: usize, degree: usize) -> Vec<f64> {
+    if data.len() <= window || window < degree + 1 {
+        return data.to_vec();
+    }
+
+    let window = if window % 2 == 0 { window + 1 } else { window };
+    let m = window / 2;
+
+    let Some(coeffs) = savgol_coefficients(window, degree) else {
+        return data.to_vec();
+    };
+
+    let mut result = Vec::with_capacity(data.len());
+    for i in 0..data.len() {
+        if i >= m && i + m < data.len() {
+            let slice = &data[i - m..=i + m];
+            let smoothed: f64 = slice.iter().zip(coeffs.iter()).map(|(v, c)| v * c).sum();
+            result.push(smoothed);
+        } else {
+            result.push(fit_and_evaluate_edge(data, i, m, degree));
+        }
+    }
+    result
+}
+
+// Shared second half of the extraction pipeline: bins matched pixel
+// coordinates by x, collapses each bin to an outlier-filtered median y,
+// smooths the resulting series with `savgol_smooth_poly`, then applies the
+// configured axis scale factors. `points` must be unscaled logical
+// coordinates. Every extraction variant except `extract_curves_fast`
+// (which repeats these steps inline) routes through this, so none of them
+// falls back to a raw, unsmoothed pixel scatter.
+fn bin_and_smooth_curve(base_color: &str, points: Vec<(f64, f64)>, config: &GraphConfig) -> (Vec<CurvePoint>, usize) {
+    let mut data: HashMap<i64, Vec<f64>> = HashMap::new();
+    for (x, y) in points {
+        let bin_x = (x / BIN_SIZE).round() as i64;
+        data.entry(bin_x).or_insert_with(Vec::new).push(y);
+    }
+
+    let mut final_points = Vec::new();
+    for (bin_x, y_vals) in data {
+        if y_vals.is_empty() {
+            continue;
+        }
+
+        let mut sorted_y = y_vals.clone();
+        sorted_y.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let median = if sorted_y.len() % 2 == 0 {
+            (sorted_y[sorted_y.len() / 2 - 1] + sorted_y[sorted_y.len() / 2]) / 2.0
+        } else {
+            sorted_y[sorted_y.len() / 2]
+        };
+
+        let filtered: Vec<f64> = sorted_y.into_iter()
+            .filter(|&y| (y - median).abs() < 2.0 * 0.2)
+            .collect();
+
+        if !filtered.is_empty() {
+            let x_val = bin_x as f64 * BIN_SIZE;
+            let y_val = filtered.iter().sum::<f64>() / filtered.len() as f64;
+            final_points.push((x_val, y_val));
+        }
+    }
+
+    final_points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let point_count = final_points.len();
+
+    let x_vals: Vec<f64> = final_points.iter().map(|(x, _)| *x).collect();
+    let y_vals: Vec<f64> = final_points.iter().map(|(_, y)| *y).collect();
+
+    let smooth_window = match base_color {
+        "red" => (y_vals.len() / 10).max(5).min(25),
+        "blue" => (y_vals.len() / 12).max(5).min(20),
+        _ => (y_vals.len() / 15).max(3).min(15),
+    };
+
+    let smoothed_y = if y_vals.len() > smooth_window {
+        savgol_smooth_poly(&y_vals, smooth_window, 2)
+    } else {
+        y_vals
+    };
+
+    let scaled_x: Vec<f64> = x_vals.iter().map(|x| x * config.x_scale).collect();
+    let scaled_y: Vec<f64> = smoothed_y.iter().map(|y| y * config.y_scale).collect();
+
+    let curve_points = scaled_x
+        .iter()
+        .zip(scaled_y.iter())
+        .map(|(x, y)| CurvePoint {
+            x: *x,
+            y: *y,
+            label: Some(format!("{:.3}, {:.3}", x, y)),
+            confidence: None,
+        })
+        .collect();
+
+    (curve_points, point_count)
+}
+
+// Main curve extraction function - enhanced version
+pub fn extract_curves(
+    image_data: &[u8],
+    selected_colors: &[String],
+    config: &GraphConfig,
+) -> Result<ExtractionResult, String> {
+    // Load image
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    
+    let rgb_image = image.to_rgb8();
+    let (width, height) = rgb_image.dimensions();
+    
+    // Auto-detect grid size
+    let (_rows, _cols) = auto_detect_grid_size(&rgb_image);
+    
+    // Get color ranges
+    let color_ranges = get_color_ranges();
+    
+    // Process each selected color
+    let mut base_color_points: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+    
+    for color_name in selected_colors {
+        let color_key = color_name.to_lowercase();
+        
+        if let Some(color_range) = color_ranges.get(color_key.as_str()) {
+            // Create color mask
+            let mask = create_color_mask(&rgb_image, color_range);
+            
+            // Apply morphological operations
+            let cleaned_mask = morphological_open(&mask, width as usize, height as usize);
+            
+            // Filter connected components with enhanced minimum size
+            let min_size = (width * height / 1000).max(1000) as usize; // Adaptive minimum size
+            let filtered_mask = filter_connected_components(&cleaned_mask, width as usize, height as usize, min_size);
+            
+            // Extract points with enhanced coordinate conversion
+            let mut points = Vec::new();
+            for y in 0..height {
+                for x in 0..width {
+                    let idx = (y * width + x) as usize;
+                    if filtered_mask[idx] {
+                        // Enhanced coordinate conversion
+                        let logical_x = if config.x_scale_type == "linear" {
+                            x as f64 * (config.x_max - config.x_min) / width as f64 + config.x_min
+                        } else {
+                            let f = x as f64 / width as f64;
+                            let log_x = config.x_min.ln() + f * (config.x_max.ln() - config.x_min.ln());
+                            log_x.exp()
+                        };
+                        
+                        let logical_y = if config.y_scale_type == "linear" {
+                            (height - y) as f64 * (config.y_max - config.y_min) / height as f64 + config.y_min
+                        } else {
+                            let f = (height - y) as f64 / height as f64;
+                            let log_y = config.y_min.ln() + f * (config.y_max.ln() - config.y_min.ln());
+                            log_y.exp()
+                        };
+                        
+                        points.push((logical_x, logical_y));
+                    }
+                }
+            }
+            
+            // Group by base color
+            let base_color = color_range.base_color.to_string();
+            base_color_points.entry(base_color).or_insert_with(Vec::new).extend(points);
+        }
+    }
+    
+    // Process each base color
+    let mut curves = Vec::new();
+    let mut total_points = 0usize;
+
+    for (base_color, points) in base_color_points {
+        if points.is_empty() {
+            continue;
+        }
+
+        let (curve_points, point_count) = bin_and_smooth_curve(&base_color, points, config);
+        total_points += point_count;
+
+        // Get display color
+        let display_color = color_ranges.get(base_color.as_str())
+            .map(|r| r.display_color.to_string())
+            .unwrap_or_else(|| "#000000".to_string());
+
+        curves.push(CurveData {
+            name: base_color.clone(),
+            color: display_color,
+            points: curve_points,
+            representation: Some(base_color.clone()),
+            point_count: Some(point_count),
+            metadata: None,
+        });
+    }
+    
+    Ok(ExtractionResult {
+        success: true,
+        curves,
+        total_points,
+        processing_time: 0.0, // Placeholder for actual processing time
+        error: None,
+        metadata: Some(ExtractionMetadata {
+            image_width: Some(width as usize),
+            image_height: Some(height as usize),
+            detected_colors: Some(color_ranges.len()),
+            extraction_method: Some("curve_extraction".to_string()),
+            quality_score: None,
+        }),
+    })
+}
+
+// Plot-area detection and perspective rectification. Scanned/photographed
+// datasheet graphs are often skewed or keystoned, which corrupts the
+// linear/log coordinate mapping in `extract_curves` since it assumes the
+// plot box fills the image exactly. This locates the rectangular plot
+// region and warps it to a clean axis-aligned rectangle before extraction.
+const DARK_THRESHOLD: f32 = 0.35;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PlotBounds {
+    pub top_left: (f64, f64),
+    pub top_right: (f64, f64),
+    pub bottom_left: (f64, f64),
+    pub bottom_right: (f64, f64),
+}
+
+fn grayscale(image: &RgbImage) -> Vec<f32> {
+    image
+        .pixels()
+        .map(|p| (p[0] as f32 * 0.299 + p[1] as f32 * 0.587 + p[2] as f32 * 0.114) / 255.0)
+        .collect()
+}
+
+// Finds the four extreme corners of the largest near-rectangular dark
+// border (the axis frame) by extremal x+y / x-y sums, which is a standard
+// cheap way to locate a skewed quadrilateral's corners without a full
+// contour search.
+fn find_plot_corners(image: &RgbImage) -> PlotBounds {
+    let (width, height) = image.dimensions();
+    let gray = grayscale(image);
+
+    let mut dark_points: Vec<(f64, f64)> = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let idx = (y * width + x) as usize;
+            if gray[idx] < DARK_THRESHOLD {
+                dark_points.push((x as f64, y as f64));
+            }
+        }
+    }
+
+    if dark_points.is_empty() {
+        return PlotBounds {
+            top_left: (0.0, 0.0),
+            top_right: (width as f64 - 1.0, 0.0),
+            bottom_left: (0.0, height as f64 - 1.0),
+            bottom_right: (width as f64 - 1.0, height as f64 - 1.0),
+        };
+    }
+
+    let top_left = *dark_points
+        .iter()
+        .min_by(|a, b| (a.0 + a.1).partial_cmp(&(b.0 + b.1)).unwrap())
+        .unwrap();
+    let bottom_right = *dark_points
+        .iter()
+        .max_by(|a, b| (a.0 + a.1).partial_cmp(&(b.0 + b.1)).unwrap())
+        .unwrap();
+    let top_right = *dark_points
+        .iter()
+        .max_by(|a, b| (a.0 - a.1).partial_cmp(&(b.0 - b.1)).unwrap())
+        .unwrap();
+    let bottom_left = *dark_points
+        .iter()
+        .min_by(|a, b| (a.0 - a.1).partial_cmp(&(b.0 - b.1)).unwrap())
+        .unwrap();
+
+    PlotBounds {
+        top_left,
+        top_right,
+        bottom_left,
+        bottom_right,
+    }
+}
+
+// Solves an 8x8 linear system via Gaussian elimination with partial
+// pivoting. Returns `None` for a (near-)singular matrix.
+fn solve_linear_system(mut a: [[f64; 8]; 8], mut b: [f64; 8]) -> Option<[f64; 8]> {
+    for col in 0..8 {
+        let pivot_row = (col..8).max_by(|&r1, &r2| a[r1][col].abs().partial_cmp(&a[r2][col].abs()).unwrap())?;
+        if a[pivot_row][col].abs() < 1e-10 {
+            return None;
+        }
+        a.swap(col, pivot_row);
+        b.swap(col, pivot_row);
+
+        let pivot = a[col][col];
+        for c in col..8 {
+            a[col][c] /= pivot;
+        }
+        b[col] /= pivot;
+
+        for row in 0..8 {
+            if row == col {
+                continue;
+            }
+            let factor = a[row][col];
+            if factor == 0.0 {
+                continue;
+            }
+            for c in col..8 {
+                a[row][c] -= factor * a[col][c];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    Some(b)
+}
+
+// Computes the 3x3 projective homography (h8 fixed to 1) mapping each
+// `src[i]` onto `dst[i]` for four point correspondences, via the standard
+// direct linear transform reduced to an 8x8 system.
+fn compute_homography(src: &[(f64, f64); 4], dst: &[(f64, f64); 4]) -> Option<[f64; 8]> {
+    let mut a = [[0.0; 8]; 8];
+    let mut b = [0.0; 8];
+
+    for i in 0..4 {
+        let (x, y) = src[i];
+        let (px, py) = dst[i];
+
+        a[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -x * px, -y * px];
+        b[2 * i] = px;
+
+        a[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -x * py, -y * py];
+        b[2 * i + 1] = py;
+    }
+
+    solve_linear_system(a, b)
+}
+
+fn apply_homography(h: &[f64; 8], x: f64, y: f64) -> (f64, f64) {
+    let denom = h[6] * x + h[7] * y + 1.0;
+    let px = (h[0] * x + h[1] * y + h[2]) / denom;
+    let py = (h[3] * x + h[4] * y + h[5]) / denom;
+    (px, py)
+}
+
+fn bilinear_sample(image: &RgbImage, x: f64, y: f64) -> Rgb<u8> {
+    let (width, height) = image.dimensions();
+    let x = x.clamp(0.0, width as f64 - 1.0);
+    let y = y.clamp(0.0, height as f64 - 1.0);
+
+    let x0 = x.floor() as u32;
+    let y0 = y.floor() as u32;
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+
+    let fx = x - x0 as f64;
+    let fy = y - y0 as f64;
+
+    let p00 = image.get_pixel(x0, y0);
+    let p10 = image.get_pixel(x1, y0);
+    let p01 = image.get_pixel(x0, y1);
+    let p11 = image.get_pixel(x1, y1);
+
+    let mut out = [0u8; 3];
+    for c in 0..3 {
+        let top = p00[c] as f64 * (1.0 - fx) + p10[c] as f64 * fx;
+        let bottom = p01[c] as f64 * (1.0 - fx) + p11[c] as f64 * fx;
+        out[c] = (top * (1.0 - fy) + bottom * fy).round().clamp(0.0, 255.0) as u8;
+    }
+    Rgb(out)
+}
+
+/// Locates the rectangular plot region in a (possibly skewed/keystoned)
+/// datasheet scan and warps it to a clean axis-aligned rectangle the same
+/// size as the input image, via inverse-mapped bilinear resampling. Returns
+/// the rectified image along with the source corners that were detected.
+pub fn rectify_plot_area(image: &RgbImage) -> (RgbImage, PlotBounds) {
+    let (width, height) = image.dimensions();
+    let bounds = find_plot_corners(image);
+
+    let src = [bounds.top_left, bounds.top_right, bounds.bottom_left, bounds.bottom_right];
+    let dst = [
+        (0.0, 0.0),
+        (width as f64 - 1.0, 0.0),
+        (0.0, height as f64 - 1.0),
+        (width as f64 - 1.0, height as f64 - 1.0),
+    ];
+
+    // Homography mapping output coordinates back to source pixels, so each
+    // output pixel can be filled by inverse-mapped sampling.
+    let Some(inv_h) = compute_homography(&dst, &src) else {
+        return (image.clone(), bounds);
+    };
+
+    let mut rectified = RgbImage::new(width, height);
+    for oy in 0..height {
+        for ox in 0..width {
+            let (sx, sy) = apply_homography(&inv_h, ox as f64, oy as f64);
+            rectified.put_pixel(ox, oy, bilinear_sample(image, sx, sy));
+        }
+    }
+
+    (rectified, bounds)
+}
+
+/// Rectifies the plot area in `image_data` and re-encodes it back to PNG
+/// bytes, so any of the extraction variants can be pointed at the
+/// straightened image instead of the original skewed/photographed scan.
+pub fn rectify_image_bytes(image_data: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let rgb_image = image.to_rgb8();
+
+    let (rectified, _bounds) = rectify_plot_area(&rgb_image);
+
+    let mut buf = std::io::Cursor::new(Vec::new());
+    DynamicImage::ImageRgb8(rectified)
+        .write_to(&mut buf, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to re-encode rectified image: {}", e))?;
+
+    Ok(buf.into_inner())
+}
+
+/// Same pipeline as `extract_curves`, but rectifies the plot area first so
+/// the coordinate conversion operates on a correctly-bounded plot even for
+/// skewed/photographed datasheet scans.
+pub fn extract_curves_rectified(
+    image_data: &[u8],
+    selected_colors: &[String],
+    config: &GraphConfig,
+) -> Result<ExtractionResult, String> {
+    let rectified = rectify_image_bytes(image_data)?;
+    extract_curves(&rectified, selected_colors, config)
+}
+
+// Hough-based automatic axis and gridline calibration. `GraphConfig`
+// otherwise requires the user to hand-enter x_min/x_max/y_min/y_max and
+// assumes the plot spans the full image; this locates the axes and major
+// gridlines so the pixel->logical mapping can be anchored to detected
+// tick positions instead.
+const EDGE_THRESHOLD: f32 = 0.15;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridCalibration {
+    pub x_gridlines: Vec<f64>,
+    pub y_gridlines: Vec<f64>,
+    pub plot_left: f64,
+    pub plot_right: f64,
+    pub plot_top: f64,
+    pub plot_bottom: f64,
+}
+
+fn sobel_magnitude(gray: &[f32], width: usize, height: usize) -> Vec<f32> {
+    const GX: [[f32; 3]; 3] = [[-1.0, 0.0, 1.0], [-2.0, 0.0, 2.0], [-1.0, 0.0, 1.0]];
+    const GY: [[f32; 3]; 3] = [[-1.0, -2.0, -1.0], [0.0, 0.0, 0.0], [1.0, 2.0, 1.0]];
+
+    let mut magnitude = vec![0.0; gray.len()];
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let mut gx = 0.0;
+            let mut gy = 0.0;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    let p = gray[(y + dy - 1) * width + (x + dx - 1)];
+                    gx += p * GX[dy][dx];
+                    gy += p * GY[dy][dx];
+                }
+            }
+            magnitude[y * width + x] = (gx * gx + gy * gy).sqrt();
+        }
+    }
+    magnitude
+}
+
+// Non-max-suppresses and clusters nearby accumulator peaks (within 3px in
+// rho) into a single gridline position, weighted by vote count.
+fn find_accumulator_peaks(acc: &[u32], rho_max: i64, min_votes: u32) -> Vec<f64> {
+    let mut peaks: Vec<(i64, u32)> = Vec::new();
+    for i in 0..acc.len() {
+        let count = acc[i];
+        if count < min_votes {
+            continue;
+        }
+        let lo = i.saturating_sub(2);
+        let hi = (i + 2).min(acc.len() - 1);
+        if acc[lo..=hi].iter().all(|&c| c <= count) {
+            peaks.push((i as i64 - rho_max, count));
+        }
+    }
+
+    let mut merged = Vec::new();
+    let mut cluster: Vec<(i64, u32)> = Vec::new();
+    for p in peaks {
+        if let Some(&(last_rho, _)) = cluster.last() {
+            if p.0 - last_rho > 3 {
+                merged.push(weighted_peak_position(&cluster));
+                cluster.clear();
+            }
+        }
+        cluster.push(p);
+    }
+    if !cluster.is_empty() {
+        merged.push(weighted_peak_position(&cluster));
+    }
+    merged
+}
+
+fn weighted_peak_position(cluster: &[(i64, u32)]) -> f64 {
+    let total: u32 = cluster.iter().map(|&(_, c)| c).sum();
+    let sum: f64 = cluster.iter().map(|&(r, c)| r as f64 * c as f64).sum();
+    sum / total.max(1) as f64
+}
+
+/// Locates the axes and major gridlines via a Hough accumulator over edge
+/// pixels, restricted to near-vertical and near-horizontal lines.
+pub fn detect_grid(image: &RgbImage) -> GridCalibration {
+    let (width, height) = image.dimensions();
+    let (w, h) = (width as usize, height as usize);
+    let gray = grayscale(image);
+    let magnitude = sobel_magnitude(&gray, w, h);
+
+    let diag = ((w * w + h * h) as f64).sqrt();
+    let rho_max = diag.ceil() as i64;
+    let acc_size = (2 * rho_max + 1) as usize;
+
+    let vertical_thetas: Vec<f64> = (-5..=5).map(|d| (d as f64).to_radians()).collect();
+    let horizontal_thetas: Vec<f64> = (85..=95).map(|d| (d as f64).to_radians()).collect();
+
+    let mut vert_acc = vec![0u32; acc_size];
+    let mut horiz_acc = vec![0u32; acc_size];
+
+    for y in 0..h {
+        for x in 0..w {
+            if magnitude[y * w + x] <= EDGE_THRESHOLD {
+                continue;
+            }
+            let (xf, yf) = (x as f64, y as f64);
+            for &theta in &vertical_thetas {
+                let rho = xf * theta.cos() + yf * theta.sin();
+                let idx = (rho.round() as i64 + rho_max) as usize;
+                if idx < acc_size {
+                    vert_acc[idx] += 1;
+                }
+            }
+            for &theta in &horizontal_thetas {
+                let rho = xf * theta.cos() + yf * theta.sin();
+                let idx = (rho.round() as i64 + rho_max) as usize;
+                if idx < acc_size {
+                    horiz_acc[idx] += 1;
+                }
+            }
+        }
+    }
+
+    let vert_threshold = (*vert_acc.iter().max().unwrap_or(&0) as f64 * 0.35) as u32;
+    let horiz_threshold = (*horiz_acc.iter().max().unwrap_or(&0) as f64 * 0.35) as u32;
+
+    let x_gridlines = find_accumulator_peaks(&vert_acc, rho_max, vert_threshold.max(1));
+    let y_gridlines = find_accumulator_peaks(&horiz_acc, rho_max, horiz_threshold.max(1));
+
+    let plot_left = x_gridlines.iter().cloned().fold(f64::INFINITY, f64::min);
+    let plot_right = x_gridlines.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let plot_top = y_gridlines.iter().cloned().fold(f64::INFINITY, f64::min);
+    let plot_bottom = y_gridlines.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    GridCalibration {
+        x_gridlines,
+        y_gridlines,
+        plot_left: if plot_left.is_finite() { plot_left } else { 0.0 },
+        plot_right: if plot_right.is_finite() { plot_right } else { width as f64 },
+        plot_top: if plot_top.is_finite() { plot_top } else { 0.0 },
+        plot_bottom: if plot_bottom.is_finite() { plot_bottom } else { height as f64 },
+    }
+}
+
+fn pixel_to_logical(x: f64, y: f64, grid: &GridCalibration, config: &GraphConfig) -> (f64, f64) {
+    let plot_width = (grid.plot_right - grid.plot_left).max(1.0);
+    let plot_height = (grid.plot_bottom - grid.plot_top).max(1.0);
+
+    let fx = (x - grid.plot_left) / plot_width;
+    let fy = (grid.plot_bottom - y) / plot_height;
+
+    let logical_x = if config.x_scale_type == "linear" {
+        fx * (config.x_max - config.x_min) + config.x_min
+    } else {
+        (config.x_min.ln() + fx * (config.x_max.ln() - config.x_min.ln())).exp()
+    };
+
+    let logical_y = if config.y_scale_type == "linear" {
+        fy * (config.y_max - config.y_min) + config.y_min
+    } else {
+        (config.y_min.ln() + fy * (config.y_max.ln() - config.y_min.ln())).exp()
+    };
+
+    (logical_x, logical_y)
+}
+
+/// Same pipeline as `extract_curves`, but interpolates logical coordinates
+/// between gridlines detected by `detect_grid` instead of assuming the
+/// plot spans the full image.
+pub fn extract_curves_calibrated(
+    image_data: &[u8],
+    selected_colors: &[String],
+    config: &GraphConfig,
+) -> Result<ExtractionResult, String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let rgb_image = image.to_rgb8();
+    let (width, height) = rgb_image.dimensions();
+
+    let grid = detect_grid(&rgb_image);
+    let color_ranges = get_color_ranges();
+
+    let mut curves = Vec::new();
+    let mut total_points = 0usize;
+
+    for color_name in selected_colors {
+        let color_key = color_name.to_lowercase();
+        let Some(color_range) = color_ranges.get(color_key.as_str()) else {
+            continue;
+        };
+
+        let mask = create_color_mask(&rgb_image, color_range);
+        let cleaned_mask = morphological_open(&mask, width as usize, height as usize);
+        let min_size = (width * height / 1000).max(1000) as usize;
+        let filtered_mask = filter_connected_components(&cleaned_mask, width as usize, height as usize, min_size);
+
+        let mut points = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let idx = (y * width + x) as usize;
+                if filtered_mask[idx] {
+                    let (logical_x, logical_y) = pixel_to_logical(x as f64, y as f64, &grid, config);
+                    points.push((logical_x, logical_y));
+                }
+            }
+        }
+
+        if points.is_empty() {
+            continue;
+        }
+
+        let (curve_points, point_count) = bin_and_smooth_curve(color_range.base_color, points, config);
+        total_points += point_count;
+
+        curves.push(CurveData {
+            name: color_range.base_color.to_string(),
+            color: color_range.display_color.to_string(),
+            point_count: Some(point_count),
+            points: curve_points,
+            representation: Some(color_range.base_color.to_string()),
+            metadata: None,
+        });
+    }
+
+    Ok(ExtractionResult {
+        success: true,
+        curves,
+        total_points,
+        processing_time: 0.0,
+        error: None,
+        metadata: Some(ExtractionMetadata {
+            image_width: Some(width as usize),
+            image_height: Some(height as usize),
+            detected_colors: Some(color_ranges.len()),
+            extraction_method: Some("grid_calibrated".to_string()),
+            quality_score: None,
+        }),
+    })
+}
+
+// Adaptive palette extraction via median-cut color quantization. Unlike
+// `get_color_ranges`, which only recognizes eight fixed HSV buckets, this
+// builds the curve-color set directly from the pixels actually present in
+// the image, so non-standard hues (teal, brown, dark red grid lines) are
+// still picked up.
+struct ColorBox {
+    pixels: Vec<[u8; 3]>,
+}
+
+impl ColorBox {
+    fn channel_range(&self, channel: usize) -> u16 {
+        let mut lo = 255u8;
+        let mut hi = 0u8;
+        for p in &self.pixels {
+            lo = lo.min(p[channel]);
+            hi = hi.max(p[channel]);
+        }
+        (hi - lo) as u16
+    }
+
+    fn widest_channel(&self) -> usize {
+        (0..3)
+            .max_by_key(|&c| self.channel_range(c))
+            .unwrap_or(0)
+    }
+
+    fn average_color(&self) -> [u8; 3] {
+        let count = self.pixels.len().max(1) as u64;
+        let (mut r, mut g, mut b) = (0u64, 0u64, 0u64);
+        for p in &self.pixels {
+            r += p[0] as u64;
+            g += p[1] as u64;
+            b += p[2] as u64;
+        }
+        [(r / count) as u8, (g / count) as u8, (b / count) as u8]
+    }
+}
+
+// Median-cut quantizer: starts with one box holding all the sampled pixels
+// and repeatedly splits the box with the largest channel range along that
+// channel's median value until `k` boxes exist (or no box can be split
+// further).
+fn median_cut_quantize(pixels: &[[u8; 3]], k: usize) -> Vec<ColorBox> {
+    let mut boxes = vec![ColorBox {
+        pixels: pixels.to_vec(),
+    }];
+
+    while boxes.len() < k {
+        let split_idx = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.pixels.len() >= 2)
+            .max_by_key(|(_, b)| b.channel_range(b.widest_channel()))
+            .map(|(i, _)| i);
+
+        let Some(split_idx) = split_idx else {
+            break;
+        };
+
+        let mut to_split = boxes.remove(split_idx);
+        let channel = to_split.widest_channel();
+        to_split.pixels.sort_unstable_by_key(|p| p[channel]);
+        let mid = to_split.pixels.len() / 2;
+        let upper_half = to_split.pixels.split_off(mid);
+
+        boxes.push(to_split);
+        boxes.push(ColorBox { pixels: upper_half });
+    }
+
+    boxes
+}
+
+// A palette entry built by the same quantizer as `detect_colors_adaptive`:
+// a cluster centroid in RGB space plus its pixel support, ready to be
+// matched against pixels directly rather than through a fixed `ColorRange`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorCentroid {
+    pub label: String,
+    pub display_color: String,
+    pub rgb: [u8; 3],
+    pub pixel_count: usize,
+}
+
+const ADAPTIVE_PALETTE_SIZE: usize = 12; // K in the 8-16 range suggested for median-cut
+
+/// Build the curve-color palette from the image itself instead of matching
+/// against a fixed set of HSV buckets. Returns cluster centroids (with
+/// `hsv` and `pixel_count` populated) after dropping clusters below
+/// `MIN_COLOR_PIXELS` support and clusters that land on the near-white/
+/// near-black background (axis lines, grid lines, text).
+pub fn detect_colors_adaptive(image_data: &[u8]) -> Result<Vec<DetectedColor>, String> {
+    let centroids = build_adaptive_palette(image_data)?;
+
+    let mut detected: Vec<DetectedColor> = centroids
+        .into_iter()
+        .map(|c| {
+            let (h, s, v) = rgb_to_hsv(c.rgb[0], c.rgb[1], c.rgb[2]);
+            DetectedColor {
+                name: c.label,
+                display_name: None,
+                color: c.display_color,
+                pixel_count: c.pixel_count,
+                hsv: Some(HSV { h, s, v }),
+                confidence: None,
+            }
+        })
+        .collect();
+
+    detected.sort_by(|a, b| b.pixel_count.cmp(&a.pixel_count));
+    Ok(detected)
+}
+
+// Shared by `detect_colors_adaptive` and the palette-driven extraction
+// path: samples reasonably-saturated pixels, quantizes them with
+// median-cut, and filters the resulting boxes by pixel support and
+// distance from the background.
+fn build_adaptive_palette(image_data: &[u8]) -> Result<Vec<ColorCentroid>, String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let rgb_image = image.to_rgb8();
+
+    // Collect pixels that look like they could be curve ink: not
+    // desaturated (axis/grid/background) and not blown-out highlights.
+    let mut pixels: Vec<[u8; 3]> = Vec::new();
+    for p in rgb_image.pixels() {
+        let (_, s, v) = rgb_to_hsv(p[0], p[1], p[2]);
+        if s > 0.15 && v > 0.08 && v < 0.98 {
+            pixels.push([p[0], p[1], p[2]]);
+        }
+    }
+
+    if pixels.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let boxes = median_cut_quantize(&pixels, ADAPTIVE_PALETTE_SIZE);
+
+    let mut centroids = Vec::new();
+    for b in boxes {
+        let pixel_count = b.pixels.len();
+        if pixel_count < MIN_COLOR_PIXELS {
+            continue;
+        }
+
+        let [r, g, bl] = b.average_color();
+        let (_, s, v) = rgb_to_hsv(r, g, bl);
+        // Near-white/near-black boxes are background or axis/text, not curves.
+        if s < 0.12 || v > 0.95 || v < 0.08 {
+            continue;
+        }
+
+        centroids.push(ColorCentroid {
+            label: format!("cluster_{:02x}{:02x}{:02x}", r, g, bl),
+            display_color: format!("#{:02X}{:02X}{:02X}", r, g, bl),
+            rgb: [r, g, bl],
+            pixel_count,
+        });
+    }
+
+    Ok(centroids)
+}
+
+// Nearest-centroid match in RGB space, returning the matching centroid
+// index as long as it falls within `tolerance` (Euclidean RGB distance).
+fn nearest_centroid(pixel: [u8; 3], centroids: &[ColorCentroid], tolerance: f32) -> Option<usize> {
+    let mut best_idx = None;
+    let mut best_dist = f32::MAX;
+
+    for (i, c) in centroids.iter().enumerate() {
+        let dr = pixel[0] as f32 - c.rgb[0] as f32;
+        let dg = pixel[1] as f32 - c.rgb[1] as f32;
+        let db = pixel[2] as f32 - c.rgb[2] as f32;
+        let dist = (dr * dr + dg * dg + db * db).sqrt();
+        if dist < best_dist {
+            best_dist = dist;
+            best_idx = Some(i);
+        }
+    }
+
+    if best_dist <= tolerance {
+        best_idx
+    } else {
+        None
+    }
+}
+
+fn create_centroid_mask(image: &RgbImage, centroid_idx: usize, centroids: &[ColorCentroid], tolerance: f32) -> Vec<bool> {
+    image
+        .pixels()
+        .map(|pixel| nearest_centroid([pixel[0], pixel[1], pixel[2]], centroids, tolerance) == Some(centroid_idx))
+        .collect()
+}
+
+pub const DEFAULT_CENTROID_TOLERANCE: f32 = 40.0;
+
+/// Same pipeline as `extract_curves`, but matches pixels against an
+/// arbitrary palette of centroids (e.g. from `detect_colors_adaptive`)
+/// instead of the fixed `ColorRange` boxes, so curves drawn in
+/// non-standard hues can still be traced.
+pub fn extract_curves_with_palette(
+    image_data: &[u8],
+    centroids: &[ColorCentroid],
+    tolerance: f32,
+    config: &GraphConfig,
+) -> Result<ExtractionResult, String> {
+    let image = image::load_from_memory(image_data)
+        .map_err(|e| format!("Failed to load image: {}", e))?;
+    let rgb_image = image.to_rgb8();
+    let (width, height) = rgb_image.dimensions();
+
+    let mut curves = Vec::new();
+    let mut total_points = 0usize;
+
+    for (idx, centroid) in centroids.iter().enumerate() {
+        let mask = create_centroid_mask(&rgb_image, idx, centroids, tolerance);
+        let cleaned_mask = morphological_open(&mask, width as usize, height as usize);
+        let min_size = (width * height / 1000).max(1000) as usize;
+        let filtered_mask = filter_connected_components(&cleaned_mask, width as usize, height as usize, min_size);
+
+        let mut points = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let px_idx = (y * width + x) as usize;
+                if filtered_mask[px_idx] {
+                    let logical_x = if config.x_scale_type == "linear" {
+                        x as f64 * (config.x_max - config.x_min) / width as f64 + config.x_min
+                    } else {
+                        let f = x as f64 / width as f64;
+                        let log_x = config.x_min.ln() + f * (config.x_max.ln() - config.x_min.ln());
+                        log_x.exp()
+                    };
+
+                    let logical_y = if config.y_scale_type == "linear" {
+                        (height - y) as f64 * (config.y_max - config.y_min) / height as f64 + config.y_min
+                    } else {
+                        let f = (height - y) as f64 / height as f64;
+                        let log_y = config.y_min.ln() + f * (config.y_max.ln() - config.y_min.ln());
+                        log_y.exp()
+                    };
+
+                    points.push((logical_x, logical_y));
+                }
+            }
+        }
+
+        if points.is_empty() {
+            continue;
+        }
+
+        let (curve_points, point_count) = bin_and_smooth_curve(&centroid.label, points, config);
+        total_points += point_count;
+
+        curves.push(CurveData {
+            name: centroid.label.clone(),
+            color: centroid.display_color.clone(),
+            point_count: Some(point_count),
+            points: curve_points,
+            representation: Some(centroid.label.clone()),
+            metadata: None,
+        });
+    }
+
+    Ok(ExtractionResult {
+        success: true,
+        curves,
+        total_points,
+        processing_time: 0.0,
+        error: None,
+        metadata: Some(ExtractionMetadata {
+            image_width: Some(width as usize),
+            image_height: Some(height as usize),
+            detected_colors: Some(centroids.len()),
+            extraction_method: Some("adaptive_palette".to_string()),
+            quality_score: None,
+        }),
+    })
+}
+
+// Enhanced color detection function
+pub fn detect_colors(image_data: &[u8]) -> Result<Vec<DetectedColor>, String> {
+    // Validate input data
+    if image_data.is_empty() {
+        return Err("Image data is empty".to_string());
+    }
+    
+    // Log image data info for debugging
+    println!("Processing image data: {} bytes", image_data.len());
+    if image_data.len() >= 8 {
+        println!("First 8 bytes: {:02x?}", &image_data[..8]);
+    }
+    
+    let image = match image::load_from_memory(image_data) {
+        Ok(img) => img,
+        Err(e) => {
+            println!("Failed to load image from memory: {}", e);
+            return Err(format!("Failed to load image: {}", e));
+        }
+    };
+    
+    let rgb_image = image.to_rgb8();
+    let (width, height) = rgb_image.dimensions();
+    let total_pixels = (width * height) as usize;
+    
+    println!("Image loaded successfully: {}x{} pixels, {} total pixels", width, height, total_pixels);
+    
+    let color_ranges = get_color_ranges();
+    let mut detected_colors = Vec::new();
+    let mut processed_base_colors = std::collections::HashSet::new();
+    
+    for (color_name, color_range) in &color_ranges {
+        let mask = create_color_mask(&rgb_image, color_range);
+        let pixel_count = mask.iter().filter(|&&b| b).count();
+        
+        // Enhanced minimum threshold based on image size
+        let min_pixels = (total_pixels as f64 * 0.0005) as usize; // Reduced threshold for better detection
+        
+        println!("Color {}: {} pixels (threshold: {})", color_name, pixel_count, min_pixels);
+        
+        if pixel_count > min_pixels && !processed_base_colors.contains(color_range.base_color) {
+            detected_colors.push(DetectedColor {
+                name: color_range.base_color.to_string(),
+                display_name: Some(color_range.base_color.to_string()),
+                color: color_range.display_color.to_string(),
+                pixel_count,
+                hsv: None,
+                confidence: None,
+            });
+            processed_base_colors.insert(color_range.base_color);
+        }
+    }
+    
+    // Sort by pixel count (most prominent colors first)
+    detected_colors.sort_by(|a, b| b.pixel_count.cmp(&a.pixel_count));
+    
+    println!("Detected {} colors: {:?}", detected_colors.len(),
+             detected_colors.iter().map(|c| &c.name).collect::<Vec<_>>());
+
+    Ok(detected_colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_cut_quantize_splits_into_requested_box_count() {
+        let pixels = vec![
+            [10, 10, 10], [12, 11, 9], [11, 9, 12],
+            [200, 200, 200], [198, 202, 201], [201, 199, 203],
+        ];
+        let boxes = median_cut_quantize(&pixels, 2);
+        assert_eq!(boxes.len(), 2);
+        let total: usize = boxes.iter().map(|b| b.pixels.len()).sum();
+        assert_eq!(total, pixels.len());
+    }
+
+    #[test]
+    fn median_cut_quantize_stops_when_boxes_cannot_split_further() {
+        let pixels = vec![[5, 5, 5]];
+        let boxes = median_cut_quantize(&pixels, 4);
+        assert_eq!(boxes.len(), 1);
+    }
+
+    #[test]
+    fn homography_maps_source_corners_onto_destination_corners() {
+        let src = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let dst = [(1.0, 1.0), (6.0, 0.0), (6.0, 6.0), (0.0, 5.0)];
+        let h = compute_homography(&src, &dst).expect("homography should solve for a valid quad");
+        for i in 0..4 {
+            let (px, py) = apply_homography(&h, src[i].0, src[i].1);
+            assert!((px - dst[i].0).abs() < 1e-6);
+            assert!((py - dst[i].1).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn homography_is_identity_when_src_and_dst_match() {
+        let quad = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        let h = compute_homography(&quad, &quad).expect("identity mapping should solve");
+        let (px, py) = apply_homography(&h, 2.0, 3.0);
+        assert!((px - 2.0).abs() < 1e-6);
+        assert!((py - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn savgol_smooth_poly_leaves_a_perfect_line_unchanged() {
+        let data: Vec<f64> = (0..20).map(|i| 2.0 * i as f64 + 1.0).collect();
+        let smoothed = savgol_smooth_poly(&data, 5, 2);
+        for (original, smooth) in data.iter().zip(smoothed.iter()) {
+            assert!((original - smooth).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn savgol_smooth_poly_reduces_noise_spike() {
+        let mut data = vec![1.0; 21];
+        data[10] = 50.0;
+        let smoothed = savgol_smooth_poly(&data, 7, 2);
+        assert!(smoothed[10] < data[10]);
+    }
+
+    #[test]
+    fn savgol_smooth_poly_returns_input_when_data_is_shorter_than_window() {
+        let data = vec![1.0, 2.0, 3.0];
+        let smoothed = savgol_smooth_poly(&data, 5, 2);
+        assert_eq!(smoothed, data);
+    }
+
+    #[test]
+    fn delta_e76_is_zero_for_identical_colors() {
+        let lab = rgb_to_lab(120, 60, 200);
+        assert_eq!(delta_e76(lab, lab), 0.0);
+    }
+
+    #[test]
+    fn delta_e76_is_larger_for_more_different_colors() {
+        let red = rgb_to_lab(255, 0, 0);
+        let dark_red = rgb_to_lab(200, 0, 0);
+        let blue = rgb_to_lab(0, 0, 255);
+        assert!(delta_e76(red, blue) > delta_e76(red, dark_red));
+    }
+
+    #[test]
+    fn filter_connected_components_fast_drops_small_components() {
+        // 5x5 mask: a 3x3 block in the top-left (9 px) and a single lone
+        // pixel in the bottom-right corner, not 8-adjacent to the block.
+        let width = 5;
+        let height = 5;
+        let mut mask = vec![false; width * height];
+        for y in 0..3 {
+            for x in 0..3 {
+                mask[y * width + x] = true;
+            }
+        }
+        mask[height * width - 1] = true;
+
+        let filtered = filter_connected_components_fast(&mask, width, height, 5);
+        for y in 0..3 {
+            for x in 0..3 {
+                assert!(filtered[y * width + x], "pixel ({x},{y}) in the large component should survive");
+            }
+        }
+        assert!(!filtered[height * width - 1], "isolated single pixel should be filtered out");
+    }
+
+    #[test]
+    fn filter_connected_components_fast_keeps_components_meeting_min_size() {
+        let width = 2;
+        let height = 2;
+        let mask = vec![true, false, false, true];
+        let filtered = filter_connected_components_fast(&mask, width, height, 1);
+        assert_eq!(filtered, mask);
+    }
+} 
\ No newline at end of file
@@ -0,0 +1,301 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Mutex;
+
+use crate::storage;
+
+const DB_FILE: &str = "espice.db";
+
+#[derive(Debug, serde::Serialize)]
+struct Pdf {
+    id: String,
+    filename: String,
+    created_at: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Page {
+    id: String,
+    pdf_id: String,
+    page_number: i64,
+    content: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Datasheet {
+    id: String,
+    product_id: String,
+    filename: String,
+    file_size: i64,
+    status: String,
+    spice_model: Option<String>,
+    content_hash: String,
+    created_at: String,
+}
+
+/// Embedded SQLite-backed replacement for the old `prisma-api.js` Node
+/// subprocess: every datasheet/PDF command used to shell out to `node` and
+/// parse its stdout as JSON. This holds a single `rusqlite::Connection`
+/// behind a mutex (SQLite only allows one writer at a time anyway) and
+/// returns the same JSON-string shape the frontend already expects.
+pub struct Database {
+    conn: Mutex<Connection>,
+}
+
+impl Database {
+    pub fn new() -> Result<Self, String> {
+        let conn = Connection::open(DB_FILE).map_err(|e| format!("Failed to open database: {}", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS pdfs (
+                id TEXT PRIMARY KEY,
+                filename TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE TABLE IF NOT EXISTS pages (
+                id TEXT PRIMARY KEY,
+                pdf_id TEXT NOT NULL REFERENCES pdfs(id),
+                page_number INTEGER NOT NULL,
+                content TEXT
+            );
+            CREATE TABLE IF NOT EXISTS datasheets (
+                id TEXT PRIMARY KEY,
+                product_id TEXT NOT NULL,
+                filename TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                file_size INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                spice_model TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );",
+        )
+        .map_err(|e| format!("Failed to initialize schema: {}", e))?;
+
+        Ok(Database { conn: Mutex::new(conn) })
+    }
+
+    pub fn get_pdfs(&self) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, filename, created_at FROM pdfs ORDER BY created_at DESC")
+            .map_err(|e| e.to_string())?;
+        let pdfs = stmt
+            .query_map([], |row| {
+                Ok(Pdf {
+                    id: row.get(0)?,
+                    filename: row.get(1)?,
+                    created_at: row.get(2)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        serde_json::to_string(&pdfs).map_err(|e| e.to_string())
+    }
+
+    pub fn get_pages(&self, pdf_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT id, pdf_id, page_number, content FROM pages WHERE pdf_id = ?1 ORDER BY page_number")
+            .map_err(|e| e.to_string())?;
+        let pages = stmt
+            .query_map(params![pdf_id], |row| {
+                Ok(Page {
+                    id: row.get(0)?,
+                    pdf_id: row.get(1)?,
+                    page_number: row.get(2)?,
+                    content: row.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        serde_json::to_string(&pages).map_err(|e| e.to_string())
+    }
+
+    pub fn get_page_details(&self, page_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let page = conn
+            .query_row(
+                "SELECT id, pdf_id, page_number, content FROM pages WHERE id = ?1",
+                params![page_id],
+                |row| {
+                    Ok(Page {
+                        id: row.get(0)?,
+                        pdf_id: row.get(1)?,
+                        page_number: row.get(2)?,
+                        content: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Page not found: {}", page_id))?;
+        serde_json::to_string(&page).map_err(|e| e.to_string())
+    }
+
+    pub async fn upload_datasheet(
+        &self,
+        store: &dyn storage::Store,
+        product_id: &str,
+        filename: &str,
+        file_data: &str,
+        file_size: u64,
+    ) -> Result<String, String> {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(file_data)
+            .map_err(|e| format!("Invalid file data: {}", e))?;
+
+        // Storing by content hash means a re-upload of a PDF we've already
+        // seen reuses the existing blob in the store instead of writing a
+        // second copy, and gives us something to re-verify against later.
+        let content_hash = storage::store_blob(store, &bytes).await?;
+
+        let id = uuid_v4();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO datasheets (id, product_id, filename, content_hash, file_size, status) VALUES (?1, ?2, ?3, ?4, ?5, 'pending')",
+            params![id, product_id, filename, content_hash, file_size as i64],
+        )
+        .map_err(|e| format!("Failed to insert datasheet: {}", e))?;
+
+        let datasheet = query_datasheet(&conn, &id)?;
+        serde_json::to_string(&datasheet).map_err(|e| e.to_string())
+    }
+
+    pub fn get_datasheets_for_product(&self, product_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, product_id, filename, file_size, status, spice_model, content_hash, created_at
+                 FROM datasheets WHERE product_id = ?1 ORDER BY created_at DESC",
+            )
+            .map_err(|e| e.to_string())?;
+        let datasheets = stmt
+            .query_map(params![product_id], row_to_datasheet)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        serde_json::to_string(&datasheets).map_err(|e| e.to_string())
+    }
+
+    pub fn get_datasheet(&self, datasheet_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let datasheet = query_datasheet(&conn, datasheet_id)?;
+        serde_json::to_string(&datasheet).map_err(|e| e.to_string())
+    }
+
+    pub fn delete_datasheet(&self, datasheet_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let affected = conn
+            .execute("DELETE FROM datasheets WHERE id = ?1", params![datasheet_id])
+            .map_err(|e| format!("Failed to delete datasheet: {}", e))?;
+        if affected == 0 {
+            return Err(format!("Datasheet not found: {}", datasheet_id));
+        }
+        serde_json::to_string(&serde_json::json!({ "success": true })).map_err(|e| e.to_string())
+    }
+
+    pub fn download_spice_model(&self, datasheet_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let spice_model: Option<String> = conn
+            .query_row(
+                "SELECT spice_model FROM datasheets WHERE id = ?1",
+                params![datasheet_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Datasheet not found: {}", datasheet_id))?;
+        let spice_model = spice_model.ok_or_else(|| "SPICE model not yet generated".to_string())?;
+        Ok(spice_model)
+    }
+
+    pub fn get_datasheet_processing_status(&self, datasheet_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let status: String = conn
+            .query_row(
+                "SELECT status FROM datasheets WHERE id = ?1",
+                params![datasheet_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Datasheet not found: {}", datasheet_id))?;
+        serde_json::to_string(&serde_json::json!({ "status": status })).map_err(|e| e.to_string())
+    }
+
+    pub fn retry_datasheet_processing(&self, datasheet_id: &str) -> Result<String, String> {
+        let conn = self.conn.lock().unwrap();
+        let affected = conn
+            .execute(
+                "UPDATE datasheets SET status = 'pending' WHERE id = ?1",
+                params![datasheet_id],
+            )
+            .map_err(|e| format!("Failed to reset datasheet status: {}", e))?;
+        if affected == 0 {
+            return Err(format!("Datasheet not found: {}", datasheet_id));
+        }
+        serde_json::to_string(&serde_json::json!({ "status": "pending" })).map_err(|e| e.to_string())
+    }
+
+    /// Re-reads the datasheet's blob by its recorded content hash and
+    /// recomputes the digest, so a partially written or tampered file is
+    /// caught before it's fed into processing rather than failing obscurely
+    /// downstream.
+    pub async fn verify_datasheet(&self, store: &dyn storage::Store, datasheet_id: &str) -> Result<String, String> {
+        let content_hash: String = {
+            let conn = self.conn.lock().unwrap();
+            conn.query_row(
+                "SELECT content_hash FROM datasheets WHERE id = ?1",
+                params![datasheet_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Datasheet not found: {}", datasheet_id))?
+        };
+
+        let valid = storage::verify_blob(store, &content_hash).await?;
+        serde_json::to_string(&serde_json::json!({ "valid": valid, "contentHash": content_hash }))
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn row_to_datasheet(row: &rusqlite::Row) -> rusqlite::Result<Datasheet> {
+    Ok(Datasheet {
+        id: row.get(0)?,
+        product_id: row.get(1)?,
+        filename: row.get(2)?,
+        file_size: row.get(3)?,
+        status: row.get(4)?,
+        spice_model: row.get(5)?,
+        content_hash: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+fn query_datasheet(conn: &Connection, datasheet_id: &str) -> Result<Datasheet, String> {
+    conn.query_row(
+        "SELECT id, product_id, filename, file_size, status, spice_model, content_hash, created_at
+         FROM datasheets WHERE id = ?1",
+        params![datasheet_id],
+        row_to_datasheet,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Datasheet not found: {}", datasheet_id))
+}
+
+// No external id-generation crate is pulled in just for this; a timestamp
+// plus a process-local counter is unique enough for a local SQLite primary
+// key and keeps the dependency footprint unchanged.
+fn uuid_v4() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::SeqCst);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("ds-{:x}-{:x}", nanos, count)
+}
@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{mpsc, Semaphore};
+
+use crate::curve_extraction::{extract_curves, GraphConfig};
+use crate::rate_limit::RateLimiterState;
+use crate::AppState;
+
+const MAX_CONCURRENT_JOBS: usize = 4;
+const JOB_QUEUE_CAPACITY: usize = 256;
+
+/// The unit of work a caller can hand to the queue. Mirrors the bodies of
+/// `process_pdf_with_mcp`, `extract_curves_rust`, and
+/// `generate_spice_with_mcp` (including the same `RateLimiterState` permit
+/// before each MCP call), but runs off the command thread instead of
+/// blocking the UI for the duration of the MCP round-trip or the CPU-bound
+/// extraction pass.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type")]
+pub enum JobKind {
+    ProcessPdf {
+        file_path: String,
+    },
+    ExtractCurves {
+        image_data: Vec<u8>,
+        selected_colors: Vec<String>,
+        config: GraphConfig,
+    },
+    GenerateSpice {
+        device_name: String,
+        device_type: String,
+        model_type: String,
+        parameters: Option<serde_json::Value>,
+        extracted_data: Option<serde_json::Value>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub status: JobStatus,
+    pub progress: u8,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct JobProgressEvent {
+    job_id: String,
+    status: JobStatus,
+    progress: u8,
+}
+
+struct QueuedJob {
+    id: String,
+    kind: JobKind,
+}
+
+/// In-process background job queue for PDF processing, curve extraction,
+/// and SPICE generation. `submit_job` enqueues work and returns immediately;
+/// a worker pool capped by a `Semaphore` runs jobs off the command thread
+/// and reports progress via the `job://progress` event, so long MCP
+/// round-trips no longer block the UI.
+pub struct JobQueueState {
+    jobs: Mutex<HashMap<String, JobRecord>>,
+    sender: mpsc::Sender<QueuedJob>,
+    next_id: AtomicU64,
+}
+
+impl JobQueueState {
+    pub fn new(app_handle: AppHandle) -> Self {
+        let (sender, receiver) = mpsc::channel(JOB_QUEUE_CAPACITY);
+        spawn_worker_pool(app_handle, receiver);
+        JobQueueState {
+            jobs: Mutex::new(HashMap::new()),
+            sender,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub async fn submit(&self, kind: JobKind) -> Result<String, String> {
+        let id = format!("job-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
+        self.jobs.lock().unwrap().insert(
+            id.clone(),
+            JobRecord {
+                id: id.clone(),
+                status: JobStatus::Queued,
+                progress: 0,
+                result: None,
+                error: None,
+                duration_ms: None,
+            },
+        );
+        self.sender
+            .send(QueuedJob { id: id.clone(), kind })
+            .await
+            .map_err(|_| "Job queue is no longer accepting work".to_string())?;
+        Ok(id)
+    }
+
+    pub fn status(&self, job_id: &str) -> Option<JobRecord> {
+        self.jobs.lock().unwrap().get(job_id).cloned()
+    }
+
+    pub fn cancel(&self, job_id: &str) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get_mut(job_id) {
+            Some(record) if record.status == JobStatus::Queued => {
+                record.status = JobStatus::Cancelled;
+                Ok(())
+            }
+            Some(_) => Err("Job is already running or finished".to_string()),
+            None => Err(format!("Unknown job id: {}", job_id)),
+        }
+    }
+
+    /// Aggregates real counters from job history for `get_processing_stats`,
+    /// replacing the previous hard-coded mock response.
+    pub fn stats(&self) -> serde_json::Value {
+        let jobs = self.jobs.lock().unwrap();
+        let succeeded = jobs.values().filter(|j| j.status == JobStatus::Succeeded).count();
+        let failed = jobs.values().filter(|j| j.status == JobStatus::Failed).count();
+        let finished = succeeded + failed;
+        let success_rate = if finished > 0 {
+            succeeded as f64 / finished as f64 * 100.0
+        } else {
+            100.0
+        };
+        let durations: Vec<u64> = jobs.values().filter_map(|j| j.duration_ms).collect();
+        let average_ms = if durations.is_empty() {
+            0.0
+        } else {
+            durations.iter().sum::<u64>() as f64 / durations.len() as f64
+        };
+
+        serde_json::json!({
+            "totalProcessed": finished,
+            "successRate": success_rate,
+            "averageProcessingTime": average_ms,
+            "totalErrors": failed,
+        })
+    }
+
+    fn update<F: FnOnce(&mut JobRecord)>(&self, job_id: &str, f: F) {
+        if let Some(record) = self.jobs.lock().unwrap().get_mut(job_id) {
+            f(record);
+        }
+    }
+}
+
+fn spawn_worker_pool(app_handle: AppHandle, mut receiver: mpsc::Receiver<QueuedJob>) {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_JOBS));
+    tauri::async_runtime::spawn(async move {
+        while let Some(job) = receiver.recv().await {
+            let app_handle = app_handle.clone();
+            let semaphore = semaphore.clone();
+            tauri::async_runtime::spawn(async move {
+                let _permit = semaphore.acquire().await.expect("job semaphore closed");
+                run_job(app_handle, job).await;
+            });
+        }
+    });
+}
+
+async fn run_job(app_handle: AppHandle, job: QueuedJob) {
+    let queue = app_handle.state::<JobQueueState>();
+
+    let cancelled = matches!(
+        queue.jobs.lock().unwrap().get(&job.id),
+        Some(record) if record.status == JobStatus::Cancelled
+    );
+    if cancelled {
+        return;
+    }
+
+    queue.update(&job.id, |r| r.status = JobStatus::Running);
+    emit_progress(&app_handle, &job.id, JobStatus::Running, 0);
+
+    let started = Instant::now();
+    let outcome = match job.kind {
+        JobKind::ProcessPdf { file_path } => run_process_pdf(&app_handle, &job.id, file_path).await,
+        JobKind::ExtractCurves { image_data, selected_colors, config } => {
+            run_extract_curves(&image_data, &selected_colors, &config)
+        }
+        JobKind::GenerateSpice {
+            device_name,
+            device_type,
+            model_type,
+            parameters,
+            extracted_data,
+        } => {
+            run_generate_spice(
+                &app_handle,
+                &job.id,
+                device_name,
+                device_type,
+                model_type,
+                parameters,
+                extracted_data,
+            )
+            .await
+        }
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    match outcome {
+        Ok(result) => {
+            queue.update(&job.id, |r| {
+                r.status = JobStatus::Succeeded;
+                r.progress = 100;
+                r.result = Some(result);
+                r.duration_ms = Some(duration_ms);
+            });
+            emit_progress(&app_handle, &job.id, JobStatus::Succeeded, 100);
+        }
+        Err(error) => {
+            let progress = queue.status(&job.id).map(|r| r.progress).unwrap_or(0);
+            queue.update(&job.id, |r| {
+                r.status = JobStatus::Failed;
+                r.error = Some(error);
+                r.duration_ms = Some(duration_ms);
+            });
+            emit_progress(&app_handle, &job.id, JobStatus::Failed, progress);
+        }
+    }
+}
+
+fn emit_progress(app_handle: &AppHandle, job_id: &str, status: JobStatus, progress: u8) {
+    let _ = app_handle.emit(
+        "job://progress",
+        JobProgressEvent {
+            job_id: job_id.to_string(),
+            status,
+            progress,
+        },
+    );
+}
+
+async fn run_process_pdf(app_handle: &AppHandle, job_id: &str, file_path: String) -> Result<serde_json::Value, String> {
+    let state = app_handle.state::<AppState>();
+    app_handle.state::<RateLimiterState>().acquire("mcp").await;
+    let file_content = std::fs::read(&file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    emit_progress(app_handle, job_id, JobStatus::Running, 25);
+
+    let form = reqwest::multipart::Form::new()
+        .part("file", reqwest::multipart::Part::bytes(file_content).file_name("datasheet.pdf"));
+
+    let response = state
+        .http_client
+        .post(&format!("{}/api/process-pdf", state.mcp_url))
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    emit_progress(app_handle, job_id, JobStatus::Running, 75);
+
+    response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+fn run_extract_curves(
+    image_data: &[u8],
+    selected_colors: &[String],
+    config: &GraphConfig,
+) -> Result<serde_json::Value, String> {
+    let result = extract_curves(image_data, selected_colors, config)?;
+    serde_json::to_value(result).map_err(|e| format!("Failed to serialize extraction result: {}", e))
+}
+
+async fn run_generate_spice(
+    app_handle: &AppHandle,
+    job_id: &str,
+    device_name: String,
+    device_type: String,
+    model_type: String,
+    parameters: Option<serde_json::Value>,
+    extracted_data: Option<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let state = app_handle.state::<AppState>();
+    app_handle.state::<RateLimiterState>().acquire("mcp").await;
+    let request_data = serde_json::json!({
+        "device_name": device_name,
+        "device_type": device_type,
+        "model_type": model_type,
+        "parameters": parameters,
+        "extracted_data": extracted_data,
+    });
+    emit_progress(app_handle, job_id, JobStatus::Running, 50);
+
+    let response = state
+        .http_client
+        .post(&format!("{}/api/generate-spice", state.mcp_url))
+        .json(&request_data)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+#[tauri::command]
+pub async fn submit_job(queue: tauri::State<'_, JobQueueState>, kind: JobKind) -> Result<String, String> {
+    queue.submit(kind).await
+}
+
+#[tauri::command]
+pub fn get_job_status(queue: tauri::State<'_, JobQueueState>, job_id: String) -> Result<JobRecord, String> {
+    queue.status(&job_id).ok_or_else(|| format!("Unknown job id: {}", job_id))
+}
+
+#[tauri::command]
+pub fn cancel_job(queue: tauri::State<'_, JobQueueState>, job_id: String) -> Result<(), String> {
+    queue.cancel(&job_id)
+}
+
+#[tauri::command]
+pub fn get_processing_stats(queue: tauri::State<'_, JobQueueState>) -> Result<serde_json::Value, String> {
+    Ok(queue.stats())
+}
@@ -5,14 +5,34 @@
 )]
 
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 use std::fs;
 use std::path::Path;
 use reqwest;
-use tokio;
 use tauri::Manager;
 mod curve_extraction;
-use curve_extraction::{detect_colors, extract_curves, GraphConfig, DetectedColor, ExtractionResult};
+mod db;
+mod jobs;
+mod ollama_setup;
+mod protocol;
+mod rate_limit;
+mod spice_export;
+mod storage;
+mod updater;
+use curve_extraction::{
+    detect_colors, detect_colors_adaptive, extract_curves, extract_curves_calibrated, extract_curves_fast,
+    extract_curves_perceptual, extract_curves_with_palette, rectify_image_bytes, ColorCentroid, CurveData,
+    GraphConfig, DetectedColor, ExtractionResult, DEFAULT_CENTROID_TOLERANCE,
+};
+use db::Database;
+use jobs::{JobQueueState, submit_job, get_job_status, cancel_job, get_processing_stats};
+use ollama_setup::{
+    check_ollama_installation, generate_embeddings, generate_spice_with_ollama, get_ollama_models, install_ollama,
+    preload_model, pull_ollama_model, start_ollama,
+};
+use rate_limit::{RateLimiterState, set_rate_limit};
+use spice_export::SpiceFormat;
+use storage::{Store, StorageState};
+use updater::{check_for_update, install_update};
 
 #[derive(Serialize, Deserialize)]
 struct MCPResponse {
@@ -69,46 +89,63 @@ struct AvailableModelsResponse {
     models: Vec<serde_json::Value>,
 }
 
+/// Shared Tauri managed state: a single pooled `reqwest::Client` reused
+/// across every MCP command, plus the resolved MCP base URL, so each
+/// command no longer builds its own client or re-reads the environment
+/// variable per call.
+pub(crate) struct AppState {
+    pub(crate) http_client: reqwest::Client,
+    pub(crate) mcp_url: String,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState {
+            http_client: reqwest::Client::new(),
+            mcp_url: std::env::var("MCP_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string()),
+        }
+    }
+}
+
 #[tauri::command]
-async fn process_pdf_with_mcp(file_path: String) -> Result<MCPResponse, String> {
-    let client = reqwest::Client::new();
-    
+async fn process_pdf_with_mcp(rate_limiter: tauri::State<'_, RateLimiterState>, state: tauri::State<'_, AppState>, file_path: String) -> Result<MCPResponse, String> {
+    rate_limiter.acquire("mcp").await;
+
     // Read file
     let file_content = fs::read(&file_path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
-    
+
     // Create form data
     let form = reqwest::multipart::Form::new()
         .part("file", reqwest::multipart::Part::bytes(file_content)
             .file_name("datasheet.pdf"));
-    
-    // Get MCP server URL from environment or use localhost as fallback
-    let mcp_url = std::env::var("MCP_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-    
+
     // Send to MCP server
-    let response = client
-        .post(&format!("{}/api/process-pdf", mcp_url))
+    let response = state.http_client
+        .post(&format!("{}/api/process-pdf", state.mcp_url))
         .multipart(form)
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
-    
+
     let result: MCPResponse = response.json().await
         .map_err(|e| format!("Failed to parse response: {}", e))?;
-    
+
     Ok(result)
 }
 
 #[tauri::command]
 async fn generate_spice_with_mcp(
+    rate_limiter: tauri::State<'_, RateLimiterState>,
+    state: tauri::State<'_, AppState>,
     device_name: String,
     device_type: String,
     model_type: String,
     parameters: Option<serde_json::Value>,
     extracted_data: Option<serde_json::Value>,
 ) -> Result<SPICEGenerationResponse, String> {
-    let client = reqwest::Client::new();
-    
+    rate_limiter.acquire("mcp").await;
+
     let request_data = SPICEGenerationRequest {
         device_name,
         device_type,
@@ -116,13 +153,10 @@ async fn generate_spice_with_mcp(
         parameters,
         extracted_data,
     };
-    
-    // Get MCP server URL from environment or use localhost as fallback
-    let mcp_url = std::env::var("MCP_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-    
+
     // Send to MCP server
-    let response = client
-        .post(&format!("{}/api/generate-spice", mcp_url))
+    let response = state.http_client
+        .post(&format!("{}/api/generate-spice", state.mcp_url))
         .json(&request_data)
         .send()
         .await
@@ -136,22 +170,21 @@ async fn generate_spice_with_mcp(
 
 #[tauri::command]
 async fn fit_parameters_with_mcp(
+    rate_limiter: tauri::State<'_, RateLimiterState>,
+    state: tauri::State<'_, AppState>,
     extracted_data: serde_json::Value,
     model_type: String,
 ) -> Result<ParameterFittingResponse, String> {
-    let client = reqwest::Client::new();
-    
+    rate_limiter.acquire("mcp").await;
+
     let request_data = ParameterFittingRequest {
         extracted_data,
         model_type,
     };
-    
-    // Get MCP server URL from environment or use localhost as fallback
-    let mcp_url = std::env::var("MCP_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-    
+
     // Send to MCP server
-    let response = client
-        .post(&format!("{}/api/fit-parameters", mcp_url))
+    let response = state.http_client
+        .post(&format!("{}/api/fit-parameters", state.mcp_url))
         .json(&request_data)
         .send()
         .await
@@ -164,14 +197,11 @@ async fn fit_parameters_with_mcp(
 }
 
 #[tauri::command]
-async fn get_available_models() -> Result<AvailableModelsResponse, String> {
-    let client = reqwest::Client::new();
-    
-    // Get MCP server URL from environment or use localhost as fallback
-    let mcp_url = std::env::var("MCP_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-    
-    let response = client
-        .get(&format!("{}/api/models", mcp_url))
+async fn get_available_models(rate_limiter: tauri::State<'_, RateLimiterState>, state: tauri::State<'_, AppState>) -> Result<AvailableModelsResponse, String> {
+    rate_limiter.acquire("mcp").await;
+
+    let response = state.http_client
+        .get(&format!("{}/api/models", state.mcp_url))
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
@@ -183,14 +213,9 @@ async fn get_available_models() -> Result<AvailableModelsResponse, String> {
 }
 
 #[tauri::command]
-async fn check_mcp_server_health() -> Result<MCPResponse, String> {
-    let client = reqwest::Client::new();
-    
-    // Get MCP server URL from environment or use localhost as fallback
-    let mcp_url = std::env::var("MCP_SERVER_URL").unwrap_or_else(|_| "http://localhost:8000".to_string());
-    
-    let response = client
-        .get(&format!("{}/health", mcp_url))
+async fn check_mcp_server_health(state: tauri::State<'_, AppState>) -> Result<MCPResponse, String> {
+    let response = state.http_client
+        .get(&format!("{}/health", state.mcp_url))
         .send()
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
@@ -202,156 +227,96 @@ async fn check_mcp_server_health() -> Result<MCPResponse, String> {
 }
 
 #[tauri::command]
-fn get_pdfs() -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("getPdfs")
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn get_pdfs(db: tauri::State<'_, Database>) -> Result<String, String> {
+    db.get_pdfs()
 }
 
 #[tauri::command]
-fn get_pages(pdf_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("getPages")
-        .arg(pdf_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn get_pages(db: tauri::State<'_, Database>, pdf_id: String) -> Result<String, String> {
+    db.get_pages(&pdf_id)
 }
 
 #[tauri::command]
-fn get_page_details(page_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("getPageDetails")
-        .arg(page_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn get_page_details(db: tauri::State<'_, Database>, page_id: String) -> Result<String, String> {
+    db.get_page_details(&page_id)
 }
 
 // Datasheet commands
 #[tauri::command]
-fn upload_datasheet(product_id: String, filename: String, file_data: String, file_size: u64) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("uploadDatasheet")
-        .arg(product_id)
-        .arg(filename)
-        .arg(file_data)
-        .arg(file_size.to_string())
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+async fn upload_datasheet(
+    db: tauri::State<'_, Database>,
+    storage: tauri::State<'_, StorageState>,
+    product_id: String,
+    filename: String,
+    file_data: String,
+    file_size: u64,
+) -> Result<String, String> {
+    db.upload_datasheet(storage.store().as_ref(), &product_id, &filename, &file_data, file_size).await
 }
 
 #[tauri::command]
-fn get_datasheets_for_product(product_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("getDatasheetsForProduct")
-        .arg(product_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn get_datasheets_for_product(db: tauri::State<'_, Database>, product_id: String) -> Result<String, String> {
+    db.get_datasheets_for_product(&product_id)
 }
 
 #[tauri::command]
-fn get_datasheet(datasheet_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("getDatasheet")
-        .arg(datasheet_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn get_datasheet(db: tauri::State<'_, Database>, datasheet_id: String) -> Result<String, String> {
+    db.get_datasheet(&datasheet_id)
 }
 
 #[tauri::command]
-fn delete_datasheet(datasheet_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("deleteDatasheet")
-        .arg(datasheet_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn delete_datasheet(db: tauri::State<'_, Database>, datasheet_id: String) -> Result<String, String> {
+    db.delete_datasheet(&datasheet_id)
 }
 
 #[tauri::command]
-fn download_spice_model(datasheet_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("downloadSpiceModel")
-        .arg(datasheet_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn download_spice_model(db: tauri::State<'_, Database>, datasheet_id: String) -> Result<String, String> {
+    db.download_spice_model(&datasheet_id)
 }
 
 #[tauri::command]
-fn get_datasheet_processing_status(datasheet_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("getDatasheetProcessingStatus")
-        .arg(datasheet_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn list_export_formats() -> Vec<spice_export::FormatInfo> {
+    spice_export::list_formats()
 }
 
 #[tauri::command]
-fn retry_datasheet_processing(datasheet_id: String) -> Result<String, String> {
-    let output = Command::new("node")
-        .arg("src-tauri/prisma-api.js")
-        .arg("retryDatasheetProcessing")
-        .arg(datasheet_id)
-        .output()
-        .map_err(|e| e.to_string())?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+fn export_spice_model(
+    db: tauri::State<'_, Database>,
+    datasheet_id: String,
+    format: SpiceFormat,
+    out_path: String,
+) -> Result<String, String> {
+    let model_body = db.download_spice_model(&datasheet_id)?;
+
+    let datasheet_json = db.get_datasheet(&datasheet_id)?;
+    let datasheet: serde_json::Value = serde_json::from_str(&datasheet_json).map_err(|e| e.to_string())?;
+    let device_name = datasheet["filename"]
+        .as_str()
+        .map(|f| f.trim_end_matches(".pdf").to_string())
+        .unwrap_or_else(|| datasheet_id.clone());
+
+    let rendered = spice_export::render(format, &device_name, &model_body);
+    fs::write(&out_path, rendered).map_err(|e| format!("Failed to write {}: {}", out_path, e))?;
+    Ok(out_path)
+}
+
+#[tauri::command]
+fn get_datasheet_processing_status(db: tauri::State<'_, Database>, datasheet_id: String) -> Result<String, String> {
+    db.get_datasheet_processing_status(&datasheet_id)
+}
+
+#[tauri::command]
+fn retry_datasheet_processing(db: tauri::State<'_, Database>, datasheet_id: String) -> Result<String, String> {
+    db.retry_datasheet_processing(&datasheet_id)
+}
+
+#[tauri::command]
+async fn verify_datasheet(
+    db: tauri::State<'_, Database>,
+    storage: tauri::State<'_, StorageState>,
+    datasheet_id: String,
+) -> Result<String, String> {
+    db.verify_datasheet(storage.store().as_ref(), &datasheet_id).await
 }
 
 #[tauri::command]
@@ -368,9 +333,13 @@ fn extract_curves_rust(
     extract_curves(&image_data, &selected_colors, &config)
 }
 
+// `detect_colors_rust` keeps the fixed HSV buckets `extract_curves_rust`
+// matches against; `detect_colors_enhanced` instead builds the palette from
+// the image itself via median-cut quantization, so curves drawn in
+// non-standard hues still show up.
 #[tauri::command]
 fn detect_colors_enhanced(image_data: Vec<u8>) -> Result<Vec<DetectedColor>, String> {
-    detect_colors(&image_data)
+    detect_colors_adaptive(&image_data)
 }
 
 #[tauri::command]
@@ -378,141 +347,116 @@ fn extract_curves_enhanced(
     image_data: Vec<u8>,
     selected_colors: Vec<String>,
     config: GraphConfig,
+    palette: Option<Vec<ColorCentroid>>,
+    rectify: Option<bool>,
+    auto_calibrate: Option<bool>,
+    match_tolerance: Option<f32>,
+    fast_path: Option<bool>,
 ) -> Result<ExtractionResult, String> {
-    extract_curves(&image_data, &selected_colors, &config)
+    // Scanned/photographed datasheet graphs are often skewed or keystoned;
+    // rectify straightens the plot area before any of the strategies below
+    // run, so set this alongside palette/fast_path/auto_calibrate/
+    // match_tolerance rather than as an alternative to them.
+    let rectified_bytes;
+    let image_data: &[u8] = if rectify.unwrap_or(false) {
+        rectified_bytes = rectify_image_bytes(&image_data)?;
+        &rectified_bytes
+    } else {
+        &image_data
+    };
+
+    if let Some(centroids) = palette {
+        return extract_curves_with_palette(image_data, &centroids, DEFAULT_CENTROID_TOLERANCE, &config);
+    }
+    // Parallel mask generation and union-find component labeling; set this
+    // for large batch extraction where the per-pixel/per-color DFS path is
+    // too slow, at no change to the filtered result.
+    if fast_path.unwrap_or(false) {
+        return extract_curves_fast(image_data, &selected_colors, &config);
+    }
+    // Anchors the pixel->logical mapping to Hough-detected gridlines
+    // instead of assuming the plot spans the full image; set this when
+    // GraphConfig's hand-entered axis bounds don't match the plot exactly.
+    if auto_calibrate.unwrap_or(false) {
+        return extract_curves_calibrated(image_data, &selected_colors, &config);
+    }
+    // Matches pixels to curve colors by CIELAB ΔE distance instead of HSV
+    // interval tests; pass a ΔE tolerance (e.g. 10-20) to use it.
+    if let Some(tolerance) = match_tolerance {
+        return extract_curves_perceptual(image_data, &selected_colors, &config, Some(tolerance));
+    }
+    extract_curves(image_data, &selected_colors, &config)
 }
 
 #[tauri::command]
-fn save_curves_to_database(
+async fn save_curves_to_database(
+    storage: tauri::State<'_, StorageState>,
     product_id: String,
     curves: Vec<CurveData>,
-    config: GraphConfig,
+    _config: GraphConfig,
 ) -> Result<String, String> {
-    // For now, just return success - database integration can be added later
-    Ok("Curves saved successfully".to_string())
+    let csv = curves_to_csv(&curves);
+    let key = format!("csv/{}/curves-{}.csv", product_id, std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0));
+    storage.store().put(&key, csv.as_bytes()).await?;
+    Ok(key)
+}
+
+fn curves_to_csv(curves: &[CurveData]) -> String {
+    let mut csv = String::from("curve,x,y,label\n");
+    for curve in curves {
+        for point in &curve.points {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                curve.name,
+                point.x,
+                point.y,
+                point.label.clone().unwrap_or_default()
+            ));
+        }
+    }
+    csv
 }
 
-#[tauri::command]
-fn get_processing_stats() -> Result<serde_json::Value, String> {
-    // Mock processing statistics
-    let stats = serde_json::json!({
-        "totalProcessed": 0,
-        "successRate": 100.0,
-        "averageProcessingTime": 0.0,
-        "lastProcessed": null,
-        "totalErrors": 0,
-        "averageQueueTime": 0.0
-    });
-    Ok(stats)
-}
+
+// Curve extraction used to be a separate FastAPI process reached over
+// localhost:8002, started via a `.bat` script and polled for health. It's
+// now served in-process through the `espice://` custom protocol (see
+// `protocol`), so these commands no longer spawn anything or depend on a
+// port being free; they just report the state frontends already expect.
 
 #[tauri::command]
 async fn start_curve_extraction_service() -> Result<ServiceStatusResponse, String> {
-    let app_handle = tauri::AppHandle::default();
-    
-    // Get the app directory
-    let app_dir = app_handle.path_resolver()
-        .app_dir()
-        .ok_or("Could not determine app directory")?;
-    
-    // Navigate to the project root (assuming we're in src-tauri)
-    let project_root = app_dir.parent()
-        .and_then(|p| p.parent())
-        .and_then(|p| p.parent())
-        .ok_or("Could not determine project root")?;
-    
-    let script_path = project_root.join("scripts").join("start-curve-extraction-service-simple.bat");
-    
-    if !script_path.exists() {
-        return Ok(ServiceStatusResponse {
-            success: false,
-            status: "error".to_string(),
-            message: None,
-            error: Some("Service start script not found".to_string()),
-        });
-    }
-    
-    // Start the service in a separate process
-    let result = Command::new("cmd")
-        .args(&["/C", script_path.to_str().unwrap()])
-        .current_dir(project_root)
-        .spawn();
-    
-    match result {
-        Ok(_child) => {
-            // Wait a moment for the service to start
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-            
-            // Check if service is now available
-            match check_curve_extraction_service_health().await {
-                Ok(_) => Ok(ServiceStatusResponse {
-                    success: true,
-                    status: "started".to_string(),
-                    message: Some("Service started successfully".to_string()),
-                    error: None,
-                }),
-                Err(_) => Ok(ServiceStatusResponse {
-                    success: false,
-                    status: "starting".to_string(),
-                    message: Some("Service is starting, please wait...".to_string()),
-                    error: None,
-                }),
-            }
-        },
-        Err(e) => Ok(ServiceStatusResponse {
-            success: false,
-            status: "error".to_string(),
-            message: None,
-            error: Some(format!("Failed to start service: {}", e)),
-        }),
-    }
+    Ok(ServiceStatusResponse {
+        success: true,
+        status: "started".to_string(),
+        message: Some("Curve extraction runs in-process; nothing to start".to_string()),
+        error: None,
+    })
 }
 
 #[tauri::command]
 async fn check_curve_extraction_service_health() -> Result<ServiceStatusResponse, String> {
-    let client = reqwest::Client::new();
-    
-    // Try to connect to the FastAPI service
-    let response = client
-        .get("http://localhost:8002/health")
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-        .await;
-    
-    match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                Ok(ServiceStatusResponse {
-                    success: true,
-                    status: "available".to_string(),
-                    message: Some("Service is available".to_string()),
-                    error: None,
-                })
-            } else {
-                Ok(ServiceStatusResponse {
-                    success: false,
-                    status: "unavailable".to_string(),
-                    message: None,
-                    error: Some("Service responded with error status".to_string()),
-                })
-            }
-        },
-        Err(e) => Ok(ServiceStatusResponse {
-            success: false,
-            status: "unavailable".to_string(),
-            message: None,
-            error: Some(format!("Service not available: {}", e)),
-        }),
-    }
+    Ok(ServiceStatusResponse {
+        success: true,
+        status: "available".to_string(),
+        message: Some("Service is available".to_string()),
+        error: None,
+    })
 }
 
 #[tauri::command]
-async fn save_csv_file(file_path: String, content: String) -> Result<String, String> {
-    // Save CSV content to file
-    fs::write(&file_path, content)
-        .map_err(|e| format!("Failed to save CSV file: {}", e))?;
-    
-    Ok(format!("CSV saved to: {}", file_path))
+async fn save_csv_file(storage: tauri::State<'_, StorageState>, file_path: String, content: String) -> Result<String, String> {
+    let key = format!("csv/{}", file_path);
+    storage.store().put(&key, content.as_bytes()).await?;
+    Ok(format!("CSV saved to: {}", key))
+}
+
+#[tauri::command]
+async fn migrate_store(app_handle: tauri::AppHandle, from: String, to: String) -> Result<serde_json::Value, String> {
+    storage::migrate(&app_handle, &from, &to).await
 }
 
 #[tauri::command]
@@ -523,13 +467,27 @@ async fn get_unprocessed_images_for_product(product_id: String) -> Result<Vec<se
 }
 
 fn main() {
-    tauri::Builder::default()
+    let database = Database::new().expect("failed to initialize database");
+    let builder = tauri::Builder::default()
+        .manage(RateLimiterState::default())
+        .manage(AppState::default())
+        .manage(StorageState::from_env())
+        .manage(database)
+        .setup(|app| {
+            let handle = app.handle().clone();
+            app.manage(JobQueueState::new(handle));
+            Ok(())
+        });
+    let builder = protocol::install(builder);
+
+    builder
         .invoke_handler(tauri::generate_handler![
             process_pdf_with_mcp,
             generate_spice_with_mcp,
             fit_parameters_with_mcp,
             get_available_models,
             check_mcp_server_health,
+            set_rate_limit,
             get_pdfs,
             get_pages,
             get_page_details,
@@ -538,8 +496,11 @@ fn main() {
             get_datasheet,
             delete_datasheet,
             download_spice_model,
+            list_export_formats,
+            export_spice_model,
             get_datasheet_processing_status,
             retry_datasheet_processing,
+            verify_datasheet,
             detect_colors_rust,
             extract_curves_rust,
             detect_colors_enhanced,
@@ -549,7 +510,21 @@ fn main() {
             start_curve_extraction_service,
             check_curve_extraction_service_health,
             save_csv_file,
-            get_unprocessed_images_for_product
+            migrate_store,
+            get_unprocessed_images_for_product,
+            check_for_update,
+            install_update,
+            submit_job,
+            get_job_status,
+            cancel_job,
+            check_ollama_installation,
+            install_ollama,
+            start_ollama,
+            pull_ollama_model,
+            get_ollama_models,
+            generate_spice_with_ollama,
+            generate_embeddings,
+            preload_model
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,12 +1,185 @@
 use std::process::{Command, Stdio};
 use std::path::Path;
+use std::sync::OnceLock;
 use std::time::Duration;
+use futures_util::StreamExt;
+use tauri::Emitter;
 use tokio::time::sleep;
 use serde_json::Value;
+use crate::rate_limit::RateLimiterState;
+
+const OLLAMA_CHAT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+/// Where to find Ollama and how to authenticate with it. Read from
+/// `OLLAMA_HOST`/`OLLAMA_API_KEY` so teams can point ESpice at a shared GPU
+/// box or an authenticated reverse-proxied Ollama instance instead of
+/// requiring a local install.
+#[derive(Debug, Clone)]
+pub struct OllamaConfig {
+    pub base_url: String,
+    pub api_key: Option<String>,
+}
+
+impl OllamaConfig {
+    pub fn from_env() -> Self {
+        let base_url = std::env::var("OLLAMA_HOST").unwrap_or_else(|_| DEFAULT_OLLAMA_BASE_URL.to_string());
+        let api_key = std::env::var("OLLAMA_API_KEY").ok().filter(|k| !k.is_empty());
+        OllamaConfig { base_url, api_key }
+    }
+
+    fn is_remote(&self) -> bool {
+        self.base_url != DEFAULT_OLLAMA_BASE_URL
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+}
+
+// A single long-lived client is reused across every Ollama request instead
+// of constructing a fresh one per call.
+static OLLAMA_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn ollama_client() -> &'static reqwest::Client {
+    OLLAMA_CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// Options forwarded to Ollama's `/api/chat` `options` object. `num_ctx`
+/// defaults to 4096 when not supplied; models are slow on first load since
+/// they page into memory, so callers should expect a generous timeout
+/// rather than the default reqwest one.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ChatOptions {
+    pub num_ctx: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    options: Value,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ChatChunkMessage {
+    content: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ChatChunk {
+    message: Option<ChatChunkMessage>,
+    #[serde(default)]
+    done: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ChatStreamEvent {
+    content: String,
+    done: bool,
+}
+
+/// Run a chat completion against a local Ollama model, streaming partial
+/// tokens to the frontend via the `ollama://chat-chunk` event so the UI can
+/// render generation progressively. Gives ESpice a fully local fallback for
+/// SPICE/parameter generation when the MCP server is unreachable.
+#[tauri::command]
+pub async fn generate_spice_with_ollama(
+    app_handle: tauri::AppHandle,
+    rate_limiter: tauri::State<'_, RateLimiterState>,
+    model: String,
+    prompt: String,
+    options: Option<ChatOptions>,
+) -> Result<String, String> {
+    rate_limiter.acquire("ollama").await;
+    let options = options.unwrap_or_default();
+    let mut ollama_options = serde_json::json!({ "num_ctx": options.num_ctx.unwrap_or(4096) });
+    if let Some(temperature) = options.temperature {
+        ollama_options["temperature"] = serde_json::json!(temperature);
+    }
+
+    let request = ChatRequest {
+        model,
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        }],
+        stream: true,
+        options: ollama_options,
+    };
+
+    let config = OllamaConfig::from_env();
+    let request_builder = ollama_client()
+        .post(config.url("/api/chat"))
+        .timeout(Duration::from_secs(OLLAMA_CHAT_TIMEOUT_SECS))
+        .json(&request);
+
+    let response = config
+        .authorize(request_builder)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut full_content = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(newline_pos) = buffer.find('\n') {
+            let line = buffer[..newline_pos].trim().to_string();
+            buffer.drain(..=newline_pos);
+            if line.is_empty() {
+                continue;
+            }
+
+            let parsed: ChatChunk = serde_json::from_str(&line)
+                .map_err(|e| format!("Failed to parse chat chunk: {}", e))?;
+
+            let content = parsed.message.map(|m| m.content).unwrap_or_default();
+            if !content.is_empty() {
+                full_content.push_str(&content);
+            }
+
+            let _ = app_handle.emit(
+                "ollama://chat-chunk",
+                ChatStreamEvent {
+                    content,
+                    done: parsed.done,
+                },
+            );
+        }
+    }
+
+    Ok(full_content)
+}
 
 /// Check if Ollama is installed on the system
 #[tauri::command]
 pub async fn check_ollama_installation() -> Result<bool, String> {
+    if OllamaConfig::from_env().is_remote() {
+        // A remote/authenticated endpoint is configured; there's no local
+        // binary to check, so treat it as already available.
+        return Ok(true);
+    }
+
     #[cfg(target_os = "windows")]
     {
         // Check Windows installation paths
@@ -150,11 +323,16 @@ pub async fn install_ollama() -> Result<(), String> {
 /// Start Ollama server
 #[tauri::command]
 pub async fn start_ollama() -> Result<(), String> {
+    if OllamaConfig::from_env().is_remote() {
+        // Nothing to spawn locally when pointed at a remote endpoint.
+        return Ok(());
+    }
+
     // Check if Ollama is already running
     if is_ollama_running().await {
         return Ok(());
     }
-    
+
     // Start Ollama in the background
     #[cfg(target_os = "windows")]
     {
@@ -201,35 +379,133 @@ pub async fn start_ollama() -> Result<(), String> {
 
 /// Check if Ollama server is running
 async fn is_ollama_running() -> bool {
-    // Try to connect to Ollama API
-    match reqwest::get("http://localhost:11434/api/tags").await {
+    let config = OllamaConfig::from_env();
+    match config.authorize(ollama_client().get(config.url("/api/tags"))).send().await {
         Ok(response) => response.status().is_success(),
         Err(_) => false,
     }
 }
 
-/// Pull a model from Ollama
+#[derive(Debug, serde::Serialize)]
+struct PullRequest<'a> {
+    name: &'a str,
+    stream: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PullStatus {
+    status: String,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct PullProgressEvent {
+    status: String,
+    percent: Option<f32>,
+}
+
+/// Pull a model from Ollama via the streaming `/api/pull` endpoint,
+/// forwarding `{status, percent}` over the `ollama://pull-progress` event
+/// so the frontend can render a real progress bar instead of the previous
+/// `ollama pull` subprocess with stdout/stderr swallowed.
 #[tauri::command]
-pub async fn pull_ollama_model(model_name: String) -> Result<(), String> {
+pub async fn pull_ollama_model(app_handle: tauri::AppHandle, rate_limiter: tauri::State<'_, RateLimiterState>, model_name: String) -> Result<(), String> {
     // Check if model is already available
     if is_model_available(&model_name).await {
         return Ok(());
     }
-    
-    // Pull the model
-    match Command::new("ollama")
-        .args(["pull", &model_name])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status() {
-            Ok(status) if status.success() => Ok(()),
-            _ => Err(format!("Failed to pull model: {}", model_name)),
+
+    rate_limiter.acquire("ollama").await;
+    let config = OllamaConfig::from_env();
+    let request = PullRequest { name: &model_name, stream: true };
+    let response = config
+        .authorize(ollama_client().post(config.url("/api/pull")).json(&request))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(newline_pos) = buffer.find('\n') {
+            let line = buffer[..newline_pos].trim().to_string();
+            buffer.drain(..=newline_pos);
+            if line.is_empty() {
+                continue;
+            }
+
+            let parsed: PullStatus = serde_json::from_str(&line)
+                .map_err(|e| format!("Failed to parse pull status: {}", e))?;
+
+            if let Some(error) = parsed.error {
+                return Err(error);
+            }
+
+            let percent = match (parsed.total, parsed.completed) {
+                (Some(total), Some(completed)) if total > 0 => Some(completed as f32 / total as f32 * 100.0),
+                _ => None,
+            };
+
+            let is_done = parsed.status == "success";
+            let _ = app_handle.emit(
+                "ollama://pull-progress",
+                PullProgressEvent {
+                    status: parsed.status,
+                    percent,
+                },
+            );
+
+            if is_done {
+                return Ok(());
+            }
         }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, serde::Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    stream: bool,
+}
+
+/// Warms a model into memory ahead of the first real inference, by sending
+/// an empty-prompt generate request, since models page into memory slowly
+/// the first time they're used.
+#[tauri::command]
+pub async fn preload_model(rate_limiter: tauri::State<'_, RateLimiterState>, model: String) -> Result<(), String> {
+    rate_limiter.acquire("ollama").await;
+    let config = OllamaConfig::from_env();
+    let request = GenerateRequest {
+        model: &model,
+        prompt: "",
+        stream: false,
+    };
+
+    config
+        .authorize(ollama_client().post(config.url("/api/generate")).json(&request))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    Ok(())
 }
 
 /// Check if a model is available
 async fn is_model_available(model_name: &str) -> bool {
-    match reqwest::get("http://localhost:11434/api/tags").await {
+    let config = OllamaConfig::from_env();
+    match config.authorize(ollama_client().get(config.url("/api/tags"))).send().await {
         Ok(response) => {
             if let Ok(data) = response.json::<Value>().await {
                 if let Some(models) = data.get("models") {
@@ -248,8 +524,10 @@ async fn is_model_available(model_name: &str) -> bool {
 
 /// Get available models from Ollama
 #[tauri::command]
-pub async fn get_ollama_models() -> Result<Vec<String>, String> {
-    match reqwest::get("http://localhost:11434/api/tags").await {
+pub async fn get_ollama_models(rate_limiter: tauri::State<'_, RateLimiterState>) -> Result<Vec<String>, String> {
+    rate_limiter.acquire("ollama").await;
+    let config = OllamaConfig::from_env();
+    match config.authorize(ollama_client().get(config.url("/api/tags"))).send().await {
         Ok(response) => {
             if let Ok(data) = response.json::<Value>().await {
                 if let Some(models) = data.get("models") {
@@ -268,4 +546,60 @@ pub async fn get_ollama_models() -> Result<Vec<String>, String> {
         },
         Err(_) => Ok(vec![]),
     }
-} 
\ No newline at end of file
+}
+
+#[derive(Debug, serde::Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+/// Generate embeddings for each text via Ollama's `/api/embeddings`
+/// endpoint (e.g. with an embedding model like `nomic-embed-text`), so
+/// ESpice can build a local vector index of datasheet paragraphs and
+/// device parameters for similarity search without sending data to the
+/// MCP server or any cloud API. Dimensionality is inferred from the first
+/// response rather than hardcoded, and every returned vector is checked to
+/// have the same length before returning.
+#[tauri::command]
+pub async fn generate_embeddings(rate_limiter: tauri::State<'_, RateLimiterState>, model: String, texts: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+    let config = OllamaConfig::from_env();
+    let mut embeddings = Vec::with_capacity(texts.len());
+    let mut expected_dim: Option<usize> = None;
+
+    for text in &texts {
+        rate_limiter.acquire("ollama").await;
+        let request = EmbeddingRequest { model: &model, prompt: text };
+        let response = config
+            .authorize(ollama_client().post(config.url("/api/embeddings")).json(&request))
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let parsed: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse embeddings response: {}", e))?;
+
+        let dim = parsed.embedding.len();
+        match expected_dim {
+            None => expected_dim = Some(dim),
+            Some(expected) if expected != dim => {
+                return Err(format!(
+                    "Inconsistent embedding dimensionality: expected {} but got {}",
+                    expected, dim
+                ));
+            }
+            _ => {}
+        }
+
+        embeddings.push(parsed.embedding);
+    }
+
+    Ok(embeddings)
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use axum::body::Body;
+use axum::routing::post;
+use axum::Router;
+use tower::ServiceExt;
+
+use crate::curve_extraction::{detect_colors, extract_curves, GraphConfig};
+
+const PROTOCOL_SCHEME: &str = "espice";
+
+#[derive(serde::Deserialize)]
+struct DetectColorsRequest {
+    image_data: Vec<u8>,
+}
+
+#[derive(serde::Deserialize)]
+struct ExtractCurvesRequest {
+    image_data: Vec<u8>,
+    selected_colors: Vec<String>,
+    config: GraphConfig,
+}
+
+async fn detect_colors_route(
+    axum::Json(payload): axum::Json<DetectColorsRequest>,
+) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+    match detect_colors(&payload.image_data) {
+        Ok(colors) => (axum::http::StatusCode::OK, axum::Json(serde_json::json!(colors))),
+        Err(error) => (axum::http::StatusCode::BAD_REQUEST, axum::Json(serde_json::json!({ "error": error }))),
+    }
+}
+
+async fn extract_curves_route(
+    axum::Json(payload): axum::Json<ExtractCurvesRequest>,
+) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+    match extract_curves(&payload.image_data, &payload.selected_colors, &payload.config) {
+        Ok(result) => (axum::http::StatusCode::OK, axum::Json(serde_json::json!(result))),
+        Err(error) => (axum::http::StatusCode::BAD_REQUEST, axum::Json(serde_json::json!({ "error": error }))),
+    }
+}
+
+fn build_router() -> Router {
+    Router::new()
+        .route("/detect-colors", post(detect_colors_route))
+        .route("/extract-curves", post(extract_curves_route))
+}
+
+async fn handle_request(request: tauri::http::Request<Vec<u8>>) -> tauri::http::Response<Vec<u8>> {
+    let (parts, body) = request.into_parts();
+    let axum_request = axum::http::Request::from_parts(parts, Body::from(body));
+
+    let response = match build_router().oneshot(axum_request).await {
+        Ok(response) => response,
+        Err(_infallible) => {
+            return tauri::http::Response::builder()
+                .status(500)
+                .body(b"internal protocol error".to_vec())
+                .unwrap();
+        }
+    };
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes.to_vec(),
+        Err(e) => {
+            return tauri::http::Response::builder()
+                .status(500)
+                .body(format!("failed to read response body: {}", e).into_bytes())
+                .unwrap();
+        }
+    };
+
+    tauri::http::Response::from_parts(parts, bytes)
+}
+
+/// Registers the `espice://` custom protocol, routing requests through an
+/// embedded `axum::Router` that calls `detect_colors`/`extract_curves`
+/// directly. Replaces the old `.bat`-spawned FastAPI service on
+/// `localhost:8002`, so curve extraction no longer depends on an external
+/// process or a free port.
+pub fn install<R: tauri::Runtime>(builder: tauri::Builder<R>) -> tauri::Builder<R> {
+    builder.register_asynchronous_uri_scheme_protocol(PROTOCOL_SCHEME, |_ctx, request, responder| {
+        tauri::async_runtime::spawn(async move {
+            responder.respond(handle_request(request).await);
+        });
+    })
+}
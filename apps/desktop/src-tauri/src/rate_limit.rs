@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_RATE_LIMIT_RPS: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    capacity: f32,
+    tokens: f32,
+    refill_per_sec: f32,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rps: f32) -> Self {
+        let capacity = rps.max(1.0);
+        TokenBucket {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: rps.max(0.01),
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f32();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Per-provider token-bucket limiter held in Tauri managed state, so a
+/// burst of batch work can't overwhelm a single-worker backend.
+pub struct RateLimiterState {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl Default for RateLimiterState {
+    fn default() -> Self {
+        RateLimiterState {
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl RateLimiterState {
+    /// Awaits a permit for `provider`, sleeping in small increments while
+    /// the bucket refills if no token is currently available.
+    pub async fn acquire(&self, provider: &str) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let bucket = buckets
+                    .entry(provider.to_string())
+                    .or_insert_with(|| TokenBucket::new(DEFAULT_RATE_LIMIT_RPS));
+                bucket.refill();
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    None
+                } else {
+                    Some((1.0 - bucket.tokens) / bucket.refill_per_sec)
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(secs) => tokio::time::sleep(Duration::from_secs_f32(secs.max(0.01))).await,
+            }
+        }
+    }
+
+    pub fn set_rate_limit(&self, provider: &str, rps: f32) {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.insert(provider.to_string(), TokenBucket::new(rps));
+    }
+}
+
+/// Lets users tune the per-provider rate limit (requests/sec) at runtime,
+/// e.g. `set_rate_limit("ollama", 2.0)` to back off a slower local box.
+#[tauri::command]
+pub fn set_rate_limit(state: tauri::State<'_, RateLimiterState>, provider: String, rps: f32) -> Result<(), String> {
+    if rps <= 0.0 {
+        return Err("rps must be a positive number".to_string());
+    }
+    state.set_rate_limit(&provider, rps);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_bucket_starts_full() {
+        let bucket = TokenBucket::new(5.0);
+        assert_eq!(bucket.capacity, 5.0);
+        assert_eq!(bucket.tokens, 5.0);
+    }
+
+    #[test]
+    fn new_bucket_clamps_rps_to_a_minimum_capacity_of_one() {
+        let bucket = TokenBucket::new(0.1);
+        assert_eq!(bucket.capacity, 1.0);
+        assert_eq!(bucket.tokens, 1.0);
+    }
+
+    #[tokio::test]
+    async fn acquire_does_not_wait_while_tokens_remain() {
+        let state = RateLimiterState::default();
+        state.set_rate_limit("test", 3.0);
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            state.acquire("test").await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+/// The SPICE dialects `export_spice_model` can target. Each simulator
+/// wraps the same underlying model text a little differently, so this
+/// only changes the surrounding syntax, not the extracted device data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpiceFormat {
+    NgspiceSubckt,
+    LtspiceAsy,
+    PspiceLib,
+    Qucs,
+    GenericAscii,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatInfo {
+    pub format: SpiceFormat,
+    pub name: String,
+    pub file_extension: String,
+    pub description: String,
+}
+
+pub fn list_formats() -> Vec<FormatInfo> {
+    vec![
+        FormatInfo {
+            format: SpiceFormat::NgspiceSubckt,
+            name: "ngspice subcircuit".to_string(),
+            file_extension: "sub".to_string(),
+            description: "Wraps the model in a .subckt/.ends block for ngspice and other SPICE3-derived simulators".to_string(),
+        },
+        FormatInfo {
+            format: SpiceFormat::LtspiceAsy,
+            name: "LTspice model".to_string(),
+            file_extension: "lib".to_string(),
+            description: "LTspice-style .model card with a `*` comment header".to_string(),
+        },
+        FormatInfo {
+            format: SpiceFormat::PspiceLib,
+            name: "PSpice library".to_string(),
+            file_extension: "lib".to_string(),
+            description: "PSpice .LIB-compatible model wrapped with PSpice's comment convention".to_string(),
+        },
+        FormatInfo {
+            format: SpiceFormat::Qucs,
+            name: "Qucs model".to_string(),
+            file_extension: "qucs".to_string(),
+            description: "Qucs-style component definition using its `#`-prefixed comment convention".to_string(),
+        },
+        FormatInfo {
+            format: SpiceFormat::GenericAscii,
+            name: "Generic ASCII".to_string(),
+            file_extension: "txt".to_string(),
+            description: "Unwrapped model text for simulators that don't need a dialect-specific wrapper".to_string(),
+        },
+    ]
+}
+
+/// Wraps `model_body` (the model text already produced for `device_name`)
+/// in the syntax the requested dialect expects.
+pub fn render(format: SpiceFormat, device_name: &str, model_body: &str) -> String {
+    let body = model_body.trim_end();
+    match format {
+        SpiceFormat::NgspiceSubckt => format!(
+            "* Generated by ESpice\n.subckt {name}\n{body}\n.ends {name}\n",
+            name = device_name,
+            body = body
+        ),
+        SpiceFormat::LtspiceAsy => format!(
+            "* LTspice model for {name}\n* Generated by ESpice\n{body}\n",
+            name = device_name,
+            body = body
+        ),
+        SpiceFormat::PspiceLib => format!(
+            "* PSpice model library\n* Device: {name}\n.LIB\n{body}\n.ENDL\n",
+            name = device_name,
+            body = body
+        ),
+        SpiceFormat::Qucs => format!(
+            "# Qucs model for {name}\n# Generated by ESpice\n{body}\n",
+            name = device_name,
+            body = body
+        ),
+        SpiceFormat::GenericAscii => format!("{}\n", body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ngspice_subckt_wraps_body_in_subckt_block() {
+        let rendered = render(SpiceFormat::NgspiceSubckt, "M1", ".model M1 nmos");
+        assert!(rendered.starts_with("* Generated by ESpice\n.subckt M1\n"));
+        assert!(rendered.contains(".model M1 nmos"));
+        assert!(rendered.ends_with(".ends M1\n"));
+    }
+
+    #[test]
+    fn qucs_uses_hash_comment_convention() {
+        let rendered = render(SpiceFormat::Qucs, "M1", ".model M1 nmos");
+        assert!(rendered.starts_with("# Qucs model for M1\n"));
+    }
+
+    #[test]
+    fn generic_ascii_leaves_body_unwrapped() {
+        let rendered = render(SpiceFormat::GenericAscii, "M1", ".model M1 nmos\n\n");
+        assert_eq!(rendered, ".model M1 nmos\n");
+    }
+
+    #[test]
+    fn list_formats_returns_an_entry_per_format() {
+        let formats = list_formats();
+        assert_eq!(formats.len(), 5);
+    }
+}
@@ -0,0 +1,440 @@
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::Emitter;
+
+const LOCAL_STORE_ROOT: &str = "espice-data";
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+fn blob_key(digest: &str) -> String {
+    format!("blobs/{}/{}", &digest[0..2], digest)
+}
+
+/// Object storage over a namespaced key, so callers don't care whether a
+/// blob lives on the local filesystem or in an S3-compatible bucket.
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), String>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String>;
+}
+
+pub struct LocalStore {
+    root: PathBuf,
+}
+
+impl LocalStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalStore { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl Store for LocalStore {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        let path = self.path_for(key);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory for {}: {}", key, e))?;
+        }
+        std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", key, e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(self.path_for(key)).map_err(|e| format!("Failed to read {}: {}", key, e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        std::fs::remove_file(self.path_for(key)).map_err(|e| format!("Failed to delete {}: {}", key, e))
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let dir = self.path_for(prefix);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut keys: Vec<String> = collect_files(&dir)?
+            .into_iter()
+            .filter_map(|path| {
+                path.strip_prefix(&self.root)
+                    .ok()
+                    .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            })
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+fn collect_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to list {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(collect_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Bucket location and credentials for an S3-compatible object store (AWS
+/// S3, MinIO, etc.), loaded from app config rather than hard-coded.
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+pub struct S3Store {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+}
+
+impl S3Store {
+    pub fn new(config: S3Config) -> Self {
+        let credentials = aws_sdk_s3::config::Credentials::new(
+            config.access_key,
+            config.secret_key,
+            None,
+            None,
+            "espice-config",
+        );
+        let mut builder = aws_sdk_s3::Config::builder()
+            .region(aws_sdk_s3::config::Region::new(config.region))
+            .credentials_provider(credentials)
+            .behavior_version(aws_sdk_s3::config::BehaviorVersion::latest());
+        if let Some(endpoint) = config.endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+        S3Store {
+            client: aws_sdk_s3::Client::from_conf(builder.build()),
+            bucket: config.bucket,
+        }
+    }
+}
+
+#[async_trait]
+impl Store for S3Store {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(aws_sdk_s3::primitives::ByteStream::from(bytes.to_vec()))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload {}: {}", key, e))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", key, e))?;
+        let bytes = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", key, e))?;
+        Ok(bytes.into_bytes().to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to delete {}: {}", key, e))?;
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, String> {
+        let output = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list {}: {}", prefix, e))?;
+        Ok(output
+            .contents()
+            .iter()
+            .filter_map(|object| object.key().map(|k| k.to_string()))
+            .collect())
+    }
+}
+
+/// Tauri-managed selection of the active `Store`; defaults to local,
+/// set `ESPICE_STORAGE_BACKEND=s3` (plus `ESPICE_S3_*`) to switch.
+pub struct StorageState {
+    active: Arc<dyn Store>,
+    backend_name: String,
+}
+
+impl StorageState {
+    pub fn from_env() -> Self {
+        match std::env::var("ESPICE_STORAGE_BACKEND").as_deref() {
+            Ok("s3") => StorageState {
+                active: Arc::new(S3Store::new(S3Config {
+                    bucket: std::env::var("ESPICE_S3_BUCKET").unwrap_or_default(),
+                    region: std::env::var("ESPICE_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                    endpoint: std::env::var("ESPICE_S3_ENDPOINT").ok(),
+                    access_key: std::env::var("ESPICE_S3_ACCESS_KEY").unwrap_or_default(),
+                    secret_key: std::env::var("ESPICE_S3_SECRET_KEY").unwrap_or_default(),
+                })),
+                backend_name: "s3".to_string(),
+            },
+            _ => StorageState {
+                active: Arc::new(LocalStore::new(LOCAL_STORE_ROOT)),
+                backend_name: "local".to_string(),
+            },
+        }
+    }
+
+    pub fn store(&self) -> Arc<dyn Store> {
+        self.active.clone()
+    }
+
+    pub fn backend_name(&self) -> &str {
+        &self.backend_name
+    }
+}
+
+fn backend_for(name: &str) -> Result<Arc<dyn Store>, String> {
+    match name {
+        "local" => Ok(Arc::new(LocalStore::new(LOCAL_STORE_ROOT))),
+        "s3" => Ok(Arc::new(S3Store::new(S3Config {
+            bucket: std::env::var("ESPICE_S3_BUCKET").unwrap_or_default(),
+            region: std::env::var("ESPICE_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            endpoint: std::env::var("ESPICE_S3_ENDPOINT").ok(),
+            access_key: std::env::var("ESPICE_S3_ACCESS_KEY").unwrap_or_default(),
+            secret_key: std::env::var("ESPICE_S3_SECRET_KEY").unwrap_or_default(),
+        }))),
+        other => Err(format!("Unknown storage backend: {}", other)),
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct MigrateProgressEvent {
+    key: String,
+    done: usize,
+    total: usize,
+}
+
+/// Streams every object from `source` into `destination`, calling
+/// `on_progress(key, done, total)` after each key so callers can surface
+/// progress without this function needing to know how. A key already present
+/// at the destination is treated as already migrated and skipped without
+/// re-reading it from the source, so a retried call after an interruption
+/// only copies the keys it didn't get to last time; a failed key is
+/// reported rather than aborting the whole migration, so a retry only needs
+/// to redo the keys that actually failed.
+async fn migrate_keys(
+    source: &dyn Store,
+    destination: &dyn Store,
+    mut on_progress: impl FnMut(&str, usize, usize),
+) -> Result<serde_json::Value, String> {
+    let keys = source.list("").await?;
+    let total = keys.len();
+    let mut migrated = 0usize;
+    let mut skipped = 0usize;
+    let mut failed = Vec::new();
+
+    for (done, key) in keys.iter().enumerate() {
+        if destination.get(key).await.is_ok() {
+            skipped += 1;
+        } else {
+            match source.get(key).await {
+                Ok(bytes) => match destination.put(key, &bytes).await {
+                    Ok(()) => migrated += 1,
+                    Err(e) => failed.push(serde_json::json!({ "key": key, "error": e })),
+                },
+                Err(e) => failed.push(serde_json::json!({ "key": key, "error": e })),
+            }
+        }
+
+        on_progress(key, done + 1, total);
+    }
+
+    Ok(serde_json::json!({
+        "total": total,
+        "migrated": migrated,
+        "skipped": skipped,
+        "failed": failed,
+    }))
+}
+
+/// Migrates every object from the `from` backend into the `to` backend,
+/// reporting each key over the `storage://migrate-progress` event so the
+/// frontend can render a real progress bar across a long migration.
+pub async fn migrate(app_handle: &tauri::AppHandle, from: &str, to: &str) -> Result<serde_json::Value, String> {
+    let source = backend_for(from)?;
+    let destination = backend_for(to)?;
+
+    let mut result = migrate_keys(source.as_ref(), destination.as_ref(), |key, done, total| {
+        let _ = app_handle.emit(
+            "storage://migrate-progress",
+            MigrateProgressEvent { key: key.to_string(), done, total },
+        );
+    })
+    .await?;
+
+    result["from"] = serde_json::json!(from);
+    result["to"] = serde_json::json!(to);
+    Ok(result)
+}
+
+/// Writes `bytes` under a content-addressed key and returns the digest;
+/// a digest that's already stored is reused rather than rewritten.
+pub async fn store_blob(store: &dyn Store, bytes: &[u8]) -> Result<String, String> {
+    let digest = sha256_hex(bytes);
+    let key = blob_key(&digest);
+    if store.get(&key).await.is_err() {
+        store.put(&key, bytes).await?;
+    }
+    Ok(digest)
+}
+
+pub async fn read_blob(store: &dyn Store, digest: &str) -> Result<Vec<u8>, String> {
+    store.get(&blob_key(digest)).await
+}
+
+/// Re-reads the stored bytes for `digest` and checks the hash still matches.
+pub async fn verify_blob(store: &dyn Store, digest: &str) -> Result<bool, String> {
+    let bytes = read_blob(store, digest).await?;
+    Ok(sha256_hex(&bytes) == digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_store(name: &str) -> LocalStore {
+        let root = std::env::temp_dir().join(format!("espice-storage-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&root);
+        LocalStore::new(root)
+    }
+
+    #[test]
+    fn sha256_hex_matches_known_digest() {
+        assert_eq!(
+            sha256_hex(b"hello world"),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+        );
+    }
+
+    #[test]
+    fn sha256_hex_is_deterministic_and_sensitive_to_input() {
+        assert_eq!(sha256_hex(b"abc"), sha256_hex(b"abc"));
+        assert_ne!(sha256_hex(b"abc"), sha256_hex(b"abd"));
+    }
+
+    #[test]
+    fn blob_key_shards_by_first_two_digest_chars() {
+        let digest = sha256_hex(b"some bytes");
+        let key = blob_key(&digest);
+        assert_eq!(key, format!("blobs/{}/{}", &digest[0..2], digest));
+    }
+
+    #[tokio::test]
+    async fn store_blob_returns_the_sha256_digest_of_its_input() {
+        let store = test_store("store_blob_digest");
+        let digest = store_blob(&store, b"content").await.unwrap();
+        assert_eq!(digest, sha256_hex(b"content"));
+    }
+
+    #[tokio::test]
+    async fn store_blob_is_idempotent_for_the_same_bytes() {
+        let store = test_store("store_blob_idempotent");
+        let first = store_blob(&store, b"same content").await.unwrap();
+        let second = store_blob(&store, b"same content").await.unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn verify_blob_is_true_for_untampered_bytes() {
+        let store = test_store("verify_blob_ok");
+        let digest = store_blob(&store, b"trustworthy").await.unwrap();
+        assert!(verify_blob(&store, &digest).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn verify_blob_is_false_when_stored_bytes_no_longer_hash_to_the_key() {
+        let store = test_store("verify_blob_tampered");
+        let digest = store_blob(&store, b"trustworthy").await.unwrap();
+        store.put(&blob_key(&digest), b"tampered").await.unwrap();
+        assert!(!verify_blob(&store, &digest).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn migrate_keys_copies_every_source_key_to_the_destination() {
+        let source = test_store("migrate_source_copy");
+        let destination = test_store("migrate_dest_copy");
+        source.put("a", b"one").await.unwrap();
+        source.put("b", b"two").await.unwrap();
+
+        let result = migrate_keys(&source, &destination, |_, _, _| {}).await.unwrap();
+
+        assert_eq!(result["migrated"], 2);
+        assert_eq!(result["skipped"], 0);
+        assert_eq!(destination.get("a").await.unwrap(), b"one");
+        assert_eq!(destination.get("b").await.unwrap(), b"two");
+    }
+
+    #[tokio::test]
+    async fn migrate_keys_skips_keys_already_present_at_the_destination() {
+        let source = test_store("migrate_source_skip");
+        let destination = test_store("migrate_dest_skip");
+        source.put("a", b"one").await.unwrap();
+        destination.put("a", b"already there").await.unwrap();
+
+        let result = migrate_keys(&source, &destination, |_, _, _| {}).await.unwrap();
+
+        assert_eq!(result["migrated"], 0);
+        assert_eq!(result["skipped"], 1);
+        assert_eq!(destination.get("a").await.unwrap(), b"already there");
+    }
+
+    #[tokio::test]
+    async fn migrate_keys_reports_progress_for_every_key() {
+        let source = test_store("migrate_source_progress");
+        let destination = test_store("migrate_dest_progress");
+        source.put("a", b"one").await.unwrap();
+        source.put("b", b"two").await.unwrap();
+
+        let mut seen = Vec::new();
+        migrate_keys(&source, &destination, |key, done, total| {
+            seen.push((key.to_string(), done, total));
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(seen.len(), 2);
+        assert!(seen.iter().all(|(_, _, total)| *total == 2));
+        assert_eq!(seen.last().unwrap().1, 2);
+    }
+}
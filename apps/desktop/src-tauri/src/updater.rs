@@ -0,0 +1,252 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+use futures_util::StreamExt;
+use tauri::Emitter;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_MANIFEST_URL: &str = "https://espice.dev/releases/latest.json";
+
+/// Ed25519 public key used to verify release signatures. Replace with the
+/// real release key before shipping; until then this stays all-zero and
+/// `install_update` refuses to run rather than "verify" against a key
+/// nobody controls.
+const UPDATE_PUBLIC_KEY: [u8; 32] = [0u8; 32];
+
+// A single long-lived client reused for every update check/download, with a
+// short connect timeout and a bounded redirect count so a hung or
+// redirect-looping mirror fails fast instead of blocking the app.
+static UPDATE_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn update_client() -> &'static reqwest::Client {
+    UPDATE_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::limited(5))
+            .build()
+            .expect("failed to build update client")
+    })
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PlatformBuild {
+    url: String,
+    signature: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ReleaseManifest {
+    version: String,
+    platforms: std::collections::HashMap<String, PlatformBuild>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub url: String,
+    pub signature: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct UpdateProgressEvent {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+fn manifest_url() -> String {
+    std::env::var("ESPICE_UPDATE_MANIFEST_URL").unwrap_or_else(|_| DEFAULT_MANIFEST_URL.to_string())
+}
+
+fn current_platform_key() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else {
+        "linux"
+    }
+}
+
+fn parse_semver(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    Some((major, minor, patch))
+}
+
+fn is_newer(remote: &str, current: &str) -> bool {
+    match (parse_semver(remote), parse_semver(current)) {
+        (Some(remote), Some(current)) => remote > current,
+        _ => false,
+    }
+}
+
+/// Fetches the release manifest; returns `None` when already current.
+#[tauri::command]
+pub async fn check_for_update() -> Result<Option<UpdateInfo>, String> {
+    let response = update_client()
+        .get(manifest_url())
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch update manifest: {}", e))?;
+
+    let manifest: ReleaseManifest = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse update manifest: {}", e))?;
+
+    if !is_newer(&manifest.version, CURRENT_VERSION) {
+        return Ok(None);
+    }
+
+    let build = manifest
+        .platforms
+        .get(current_platform_key())
+        .ok_or_else(|| format!("No build published for platform '{}'", current_platform_key()))?;
+
+    Ok(Some(UpdateInfo {
+        version: manifest.version,
+        url: build.url.clone(),
+        signature: build.signature.clone(),
+    }))
+}
+
+fn verify_signature(data: &[u8], signature_b64: &str) -> Result<(), String> {
+    let signature_bytes = base64_decode(signature_b64)?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .map_err(|e| format!("Malformed signature: {}", e))?;
+    let verifying_key = VerifyingKey::from_bytes(&UPDATE_PUBLIC_KEY)
+        .map_err(|e| format!("Invalid embedded public key: {}", e))?;
+    verifying_key
+        .verify(data, &signature)
+        .map_err(|_| "Update signature verification failed".to_string())
+}
+
+fn base64_decode(input: &str) -> Result<Vec<u8>, String> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(input.trim())
+        .map_err(|e| format!("Invalid base64 signature: {}", e))
+}
+
+fn staged_update_path(version: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("espice-update-{}", version))
+}
+
+/// Downloads the archive in `info`, verifies its signature against the
+/// embedded public key, then stages it and triggers install/restart.
+#[tauri::command]
+pub async fn install_update(app_handle: tauri::AppHandle, info: UpdateInfo) -> Result<(), String> {
+    if UPDATE_PUBLIC_KEY == [0u8; 32] {
+        return Err("UPDATE_PUBLIC_KEY is still the placeholder; refusing to install an update that can't be verified".to_string());
+    }
+
+    let response = update_client()
+        .get(&info.url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download update: {}", e))?;
+
+    let total = response.content_length();
+    let mut downloaded: u64 = 0;
+    let mut bytes = Vec::new();
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download stream error: {}", e))?;
+        downloaded += chunk.len() as u64;
+        bytes.extend_from_slice(&chunk);
+        let _ = app_handle.emit("updater://progress", UpdateProgressEvent { downloaded, total });
+    }
+
+    verify_signature(&bytes, &info.signature)?;
+
+    let staged_path = staged_update_path(&info.version);
+    std::fs::write(&staged_path, &bytes)
+        .map_err(|e| format!("Failed to stage update: {}", e))?;
+
+    install_staged_build(&staged_path)
+}
+
+#[cfg(target_os = "windows")]
+fn install_staged_build(staged_path: &PathBuf) -> Result<(), String> {
+    std::process::Command::new(staged_path)
+        .arg("/SILENT")
+        .spawn()
+        .map_err(|e| format!("Failed to launch installer: {}", e))?;
+    std::process::exit(0);
+}
+
+#[cfg(target_os = "macos")]
+fn install_staged_build(staged_path: &PathBuf) -> Result<(), String> {
+    std::process::Command::new("open")
+        .arg(staged_path)
+        .spawn()
+        .map_err(|e| format!("Failed to launch installer: {}", e))?;
+    std::process::exit(0);
+}
+
+#[cfg(target_os = "linux")]
+fn install_staged_build(staged_path: &PathBuf) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(staged_path)
+        .map_err(|e| format!("Failed to stat staged update: {}", e))?
+        .permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(staged_path, perms)
+        .map_err(|e| format!("Failed to mark update executable: {}", e))?;
+    std::process::Command::new(staged_path)
+        .spawn()
+        .map_err(|e| format!("Failed to launch updated binary: {}", e))?;
+    std::process::exit(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_semver_parses_major_minor_patch() {
+        assert_eq!(parse_semver("1.2.3"), Some((1, 2, 3)));
+    }
+
+    #[test]
+    fn parse_semver_strips_a_leading_v() {
+        assert_eq!(parse_semver("v2.0.1"), Some((2, 0, 1)));
+    }
+
+    #[test]
+    fn parse_semver_rejects_non_numeric_or_incomplete_input() {
+        assert_eq!(parse_semver("not-a-version"), None);
+        assert_eq!(parse_semver("1.2"), None);
+    }
+
+    #[test]
+    fn is_newer_compares_semver_components_in_order() {
+        assert!(is_newer("1.2.3", "1.2.2"));
+        assert!(is_newer("2.0.0", "1.9.9"));
+        assert!(!is_newer("1.2.3", "1.2.3"));
+        assert!(!is_newer("1.2.2", "1.2.3"));
+    }
+
+    #[test]
+    fn is_newer_is_false_when_either_version_fails_to_parse() {
+        assert!(!is_newer("garbage", "1.0.0"));
+        assert!(!is_newer("1.0.0", "garbage"));
+    }
+
+    #[test]
+    fn verify_signature_rejects_malformed_base64() {
+        let err = verify_signature(b"data", "not valid base64!!").unwrap_err();
+        assert!(err.contains("Invalid base64"));
+    }
+
+    #[test]
+    fn verify_signature_fails_closed_with_the_placeholder_key() {
+        use base64::Engine;
+        let signature = base64::engine::general_purpose::STANDARD.encode([0u8; 64]);
+        assert!(verify_signature(b"some data", &signature).is_err());
+    }
+}